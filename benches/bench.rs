@@ -1,3 +1,4 @@
+#![cfg(feature="experimental_pointer_ranges")]
 #![feature(test)]
 
 extern crate test;
@@ -61,7 +61,7 @@ fn bench_binary_search_std(b: &mut Bencher) {
     data.sort();
     b.iter(|| {
         for elt in &elements {
-            let _ = black_box(data.binary_search(&elt));
+            let _ = black_box(data.binary_search(elt));
         }
     });
 }
@@ -399,7 +399,7 @@ unsafe fn split_at_unchecked<T>(data: &[T], i: usize) -> (&[T], &[T])
     use std::slice::from_raw_parts;
     debug_assert!(i <= data.len());
     let ptr = data.as_ptr();
-    (from_raw_parts(ptr, i), from_raw_parts(ptr.offset(i as isize), data.len() - i))
+    (from_raw_parts(ptr, i), from_raw_parts(ptr.add(i), data.len() - i))
 }
 
 
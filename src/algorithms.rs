@@ -7,7 +7,8 @@
 use std::cmp::{self, Ordering};
 use std::mem::swap;
 
-use crate::scope;
+use crate::{scope, Container, Range};
+use crate::container_traits::{GetUnchecked, GetUncheckedMut};
 #[cfg(feature="experimental_pointer_ranges")]
 use crate::pointer::zip;
 
@@ -85,6 +86,74 @@ pub fn quicksort_range<T: Ord>(v: &mut [T]) {
     });
 }
 
+/// Quicksort for any container that can be indexed mutably, like a
+/// `Reversed` view.
+///
+/// Unlike `quicksort_range`, it only uses indices, so short sections are
+/// insertion sorted by swapping elements.
+pub fn quicksort_indexed<Array, T>(v: Array)
+    where Array: GetUncheckedMut<Item=T>,
+          T: Ord,
+{
+    scope(v, |mut v| {
+        let range = v.range();
+        quicksort_indexed_range(range, &mut v);
+    });
+}
+
+fn quicksort_indexed_range<'id, T, Array, M>(range: Range<'id>, v: &mut Container<'id, Array, M>)
+    where T: Ord,
+          Array: GetUncheckedMut<Item=T>,
+{
+    if let Ok(range) = range.nonempty() {
+        // Fall back to insertion sort for short sections
+        if range.len() <= QS_INSERTION_SORT_THRESH {
+            insertion_sort_indexed_range(range.no_proof(), v, |x, y| x < y);
+            return;
+        }
+
+        let (l, m, r) = (range.first(), range.upper_middle(), range.last());
+
+        // simple pivot
+        // let pivot = m;
+        //
+        // smart pivot -- use median of three
+        let mut pivot = if v[l] <= v[m] && v[m] <= v[r] {
+            m
+        } else if v[m] <= v[l] && v[l] <= v[r] {
+            l
+        } else {
+            r
+        };
+
+        // partition
+        let mut scan = range;
+        v.swap(scan.first(), pivot);
+        pivot = scan.first();
+        'main: loop {
+            if v[scan.first()] >= v[pivot] {
+                loop {
+                    if v[scan.last()] <= v[pivot] {
+                        v.swap(scan.first(), scan.last());
+                        break;
+                    }
+                    if !scan.advance_back() {
+                        break 'main;
+                    }
+                }
+            }
+            if !scan.advance() {
+                break;
+            }
+        }
+
+        // ok split at pivot location and recurse
+        let (a, b, _) = range.split_at(scan.first().integer() - range.start());
+        quicksort_indexed_range(a, v);
+        quicksort_indexed_range(b, v);
+    }
+}
+
 /// Simple quicksort implemented using `indexing`’s PRange.
 #[cfg(feature="experimental_pointer_ranges")]
 pub fn quicksort_prange<T: Ord>(v: &mut [T]) {
@@ -286,6 +355,26 @@ pub fn insertion_sort_ranges<T, F>(v: &mut [T], mut less_than: F) where F: FnMut
     });
 }
 
+/// Insertion sort of the elements in `range` by swapping.
+fn insertion_sort_indexed_range<'id, T, Array, M, F>(range: Range<'id>, v: &mut Container<'id, Array, M>,
+                                                     mut less_than: F)
+    where Array: GetUncheckedMut<Item=T>,
+          F: FnMut(&T, &T) -> bool,
+{
+    if let Ok(mut i) = range.nonempty() {
+        while i.advance() {
+            let mut j = i.first();
+            let mut prev = j;
+            while v.backward(&mut prev) && prev.integer() >= range.start() &&
+                less_than(&v[j], &v[prev])
+            {
+                v.swap(j, prev);
+                j = prev;
+            }
+        }
+    }
+}
+
 /// Insertion sort using lower_bound to find the place to insert; which
 /// makes it scale better (still restricted to just a smallish number of
 /// elements).
@@ -387,7 +476,7 @@ impl From<&'static str> for AlgorithmError {
 pub fn merge_internal_indices<T: Ord>(data: &mut [T], left_end: usize, buffer: &mut [T])
     -> Result<(), AlgorithmError>
 {
-    debug_assert!(data.len() >= 1);
+    debug_assert!(!data.is_empty());
     if left_end > data.len() || left_end > buffer.len() {
         Err("merge_internal: data or buffer too short")?;
     }
@@ -444,7 +533,7 @@ pub fn merge_internal_indices<T: Ord>(data: &mut [T], left_end: usize, buffer: &
 pub fn merge_internal_ranges<T: Ord>(data: &mut [T], left_end: usize, buffer: &mut [T])
     -> Result<(), AlgorithmError>
 {
-    debug_assert!(data.len() >= 1);
+    debug_assert!(!data.is_empty());
     if left_end > data.len() || left_end > buffer.len() {
         Err("merge_internal: data or buffer too short")?;
     }
@@ -486,9 +575,11 @@ pub fn merge_internal_ranges<T: Ord>(data: &mut [T], left_end: usize, buffer: &m
     Ok(())
 }
 
-#[cfg(feature="use_std")]
+#[cfg(feature="alloc")]
 #[test]
 fn test_merge_internal() {
+    use alloc::vec::Vec;
+
     let mut buffer = [0; 128];
     let a = (0..15).collect::<Vec<_>>();
     let b = (1..25).filter(|&x| x % 2 == 0).collect::<Vec<_>>();
@@ -511,7 +602,14 @@ fn test_merge_internal() {
     }
 }
 
-pub fn heapify<T: Ord>(v: &mut [T]) {
+/// Make the container a min-heap.
+///
+/// Works for any container that can be indexed mutably, like a slice or a
+/// `Reversed` view.
+pub fn heapify<Array, T>(v: Array)
+    where Array: GetUncheckedMut<Item=T>,
+          T: Ord,
+{
     scope(v, |mut v| {
         // for 0-indexed element k, children are:
         // 2k + 1, 2k + 2
@@ -537,6 +635,26 @@ pub fn heapify<T: Ord>(v: &mut [T]) {
     });
 }
 
+#[test]
+fn test_reversed_algorithms() {
+    use crate::reversed::Reversed;
+
+    // sorting a reversed view sorts the data in descending order
+    let mut data = [0; 100];
+    for (i, x) in data.iter_mut().enumerate() {
+        *x = (i * 37) % 101;
+    }
+    quicksort_indexed(Reversed(&mut data[..]));
+    assert!(data.windows(2).all(|w| w[0] > w[1]));
+    assert_eq!(lower_bound(Reversed(&data[..]), &50), 50);
+    assert_eq!(binary_search_by(Reversed(&data[..]), |x| x.cmp(&0)), Ok(0));
+
+    // a min-heap of the reversed view
+    let mut data = [1, 2, 3, 4, 5, 6, 7];
+    heapify(Reversed(&mut data[..]));
+    assert_eq!(data[6], 1);
+}
+
 #[test]
 fn test_heapify() {
     let mut data = [8, 12, 9, 7, 22, 3, 26, 14, 11, 15, 22];
@@ -571,10 +689,14 @@ pub fn binary_search<T: Ord>(v: &[T], elt: &T) -> Result<usize, usize> {
     binary_search_by(v, |x| x.cmp(elt))
 }
 
-/// `f` is a closure that is passed `x` from the slice and should return the
-/// result of `x` compared with *something*.
-pub fn binary_search_by<T, F>(v: &[T], mut f: F) -> Result<usize, usize>
-    where F: FnMut(&T) -> Ordering,
+/// `f` is a closure that is passed `x` from the container and should return
+/// the result of `x` compared with *something*.
+///
+/// Works for any container that can be indexed, like a slice or a
+/// `Reversed` view.
+pub fn binary_search_by<Array, T, F>(v: Array, mut f: F) -> Result<usize, usize>
+    where Array: GetUnchecked<Item=T>,
+          F: FnMut(&T) -> Ordering,
 {
     scope(v, move |v| {
         let mut range = v.range();
@@ -711,8 +833,16 @@ fn test_binary_search() {
     }
 }
 
+/// Return the first position where `elt` could be inserted while keeping
+/// the order.
+///
+/// Works for any container that can be indexed, like a slice or a
+/// `Reversed` view.
 //#[inline(never)]
-pub fn lower_bound<T: PartialOrd>(v: &[T], elt: &T) -> usize {
+pub fn lower_bound<Array, T>(v: Array, elt: &T) -> usize
+    where Array: GetUnchecked<Item=T>,
+          T: PartialOrd,
+{
     scope(v, move |v| {
         let mut range = v.range();
         while let Ok(range_) = range.nonempty() {
@@ -745,8 +875,6 @@ pub fn lower_bound_prange<T: PartialOrd>(v: &[T], elt: &T) -> usize {
 }
 
 
-#[cfg(feature="experimental_pointer_ranges")]
-use crate::Container;
 #[cfg(feature="experimental_pointer_ranges")]
 use crate::Unknown;
 #[cfg(feature="experimental_pointer_ranges")]
@@ -813,11 +941,11 @@ pub fn lower_bound_pslice<T, F>(v: &[T], f: F) -> usize
 pub fn lower_bound_raw_ptr<T: PartialOrd>(v: &[T], elt: &T) -> usize {
     unsafe {
         let mut start = v.as_ptr();
-        let end = start.offset(v.len() as isize);
+        let end = start.add(v.len());
         let mut count = ptrdistance(start, end);
         while count > 0 {
             let step = count / 2;
-            let it = start.offset(step as isize);
+            let it = start.add(step);
             if *it < *elt {
                 start = it.offset(1);
                 count -= step + 1;
@@ -839,13 +967,13 @@ fn ptrdistance<T>(from: *const T, to: *const T) -> usize {
 #[test]
 fn test_lower_bound() {
     let data = [3, 7, 8, 8, 8, 11, 11, 11, 15, 22, 22, 26];
-    assert_eq!(lower_bound(&data, &8), 2);
-    assert_eq!(lower_bound(&data, &7), 1);
+    assert_eq!(lower_bound(&data[..], &8), 2);
+    assert_eq!(lower_bound(&data[..], &7), 1);
 
     let elts = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 25, 26, 27, 28];
 
     for elt in &elts {
-        assert_eq!(lower_bound(&data, elt),
+        assert_eq!(lower_bound(&data[..], elt),
             data.binary_search_by(|x| if x >= elt {
                 Ordering::Greater
             } else {
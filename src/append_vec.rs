@@ -0,0 +1,282 @@
+//! A concurrent, append-only vector.
+
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug};
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use alloc::boxed::Box;
+
+use crate::container::Container;
+use crate::container_traits::*;
+use crate::{ContainerPrivate, Index};
+
+// The first bucket holds 2^FIRST_BUCKET_BITS elements, and each bucket
+// after it holds as many as all the buckets before it together.
+const FIRST_BUCKET_BITS: u32 = 5;
+const FIRST_BUCKET_LEN: usize = 1 << FIRST_BUCKET_BITS;
+const BUCKETS: usize = (usize::BITS - FIRST_BUCKET_BITS) as usize;
+
+struct Slot<T> {
+    ready: AtomicBool,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// An append-only vector, that can be pushed to from several threads at the
+/// same time.
+///
+/// The elements are stored in a sequence of buckets of growing size, so
+/// elements never move once they are pushed, and `AppendVec::push` needs no
+/// locks.
+///
+/// In a scope, `Container::push` on `&AppendVec<T>` takes `&self` and
+/// returns a branded index to the new element. A branded index must be
+/// less than the length, so it waits until all the elements before the new
+/// one are pushed too: it blocks while another thread is in the middle of
+/// a push, which can take long if that thread is preempted.
+///
+/// ```
+/// use std::thread;
+/// use indexing::scope;
+/// use indexing::append_vec::AppendVec;
+///
+/// let symbols = AppendVec::new();
+/// scope(&symbols, |v| {
+///     let (a, b) = thread::scope(|s| {
+///         let a = s.spawn(|| v.push("a"));
+///         let b = s.spawn(|| v.push("b"));
+///         (a.join().unwrap(), b.join().unwrap())
+///     });
+///     assert_eq!((v[a], v[b]), ("a", "b"));
+/// });
+/// assert_eq!(symbols.len(), 2);
+/// ```
+pub struct AppendVec<T> {
+    buckets: [AtomicPtr<Slot<T>>; BUCKETS],
+    // number of slots that have been handed out to pushers
+    reserved: AtomicUsize,
+    // length of the prefix of slots that are all written
+    len: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for AppendVec<T> { }
+unsafe impl<T: Send + Sync> Sync for AppendVec<T> { }
+
+/// Return the bucket and the position in the bucket of index `i`.
+#[inline]
+fn location(i: usize) -> (usize, usize) {
+    let j = i + FIRST_BUCKET_LEN;
+    let bits = usize::BITS - 1 - j.leading_zeros();
+    ((bits - FIRST_BUCKET_BITS) as usize, j - (1 << bits))
+}
+
+#[inline]
+fn bucket_len(bucket: usize) -> usize {
+    FIRST_BUCKET_LEN << bucket
+}
+
+impl<T> AppendVec<T> {
+    /// Create a new empty vector.
+    pub fn new() -> Self {
+        AppendVec {
+            buckets: [const { AtomicPtr::new(ptr::null_mut()) }; BUCKETS],
+            reserved: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the number of elements.
+    ///
+    /// Elements that are still being pushed by other threads are not
+    /// counted until all the elements before them are pushed too.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Return `true` if the vector has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append `element` to the vector and return its index.
+    pub fn push(&self, element: T) -> usize {
+        // check the capacity before reserving the slot; a slot that is
+        // reserved but never written would stop the length from growing
+        let i = self.reserved.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+            if n <= usize::MAX - FIRST_BUCKET_LEN { Some(n + 1) } else { None }
+        });
+        let i = i.expect("AppendVec::push: capacity overflow");
+        let (bucket, pos) = location(i);
+        unsafe {
+            let slot = &*self.bucket(bucket).add(pos);
+            (*slot.value.get()).write(element);
+            slot.ready.store(true, Ordering::SeqCst);
+        }
+        self.publish();
+        i
+    }
+
+    /// Return the element at `index`, if it is in bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len() {
+            unsafe {
+                Some(self.xget_unchecked(index))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Return the slot at index `i`; its bucket must be allocated.
+    #[inline]
+    unsafe fn slot(&self, i: usize) -> &Slot<T> {
+        let (bucket, pos) = location(i);
+        &*self.buckets[bucket].load(Ordering::Acquire).add(pos)
+    }
+
+    /// Return the pointer to the bucket, allocating it if needed.
+    fn bucket(&self, bucket: usize) -> *mut Slot<T> {
+        let p = self.buckets[bucket].load(Ordering::Acquire);
+        if !p.is_null() {
+            return p;
+        }
+        let new = (0..bucket_len(bucket))
+            .map(|_| Slot { ready: AtomicBool::new(false), value: UnsafeCell::new(MaybeUninit::uninit()) })
+            .collect::<Box<[Slot<T>]>>();
+        let new = Box::into_raw(new) as *mut Slot<T>;
+        match self.buckets[bucket].compare_exchange(ptr::null_mut(), new,
+                                                    Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => new,
+            Err(p) => {
+                // another thread allocated it first
+                unsafe {
+                    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(new, bucket_len(bucket))));
+                }
+                p
+            }
+        }
+    }
+
+    /// Extend the length over the slots that are written.
+    ///
+    /// Each pusher calls this after marking its slot as ready, so the last
+    /// one to finish in a run of slots extends the length over all of them.
+    fn publish(&self) {
+        loop {
+            let len = self.len.load(Ordering::SeqCst);
+            if len == self.reserved.load(Ordering::SeqCst) {
+                return;
+            }
+            // the slot's bucket may not be allocated yet
+            let bucket = self.buckets[location(len).0].load(Ordering::SeqCst);
+            if bucket.is_null() {
+                return;
+            }
+            let ready = unsafe { self.slot(len).ready.load(Ordering::SeqCst) };
+            if !ready {
+                return;
+            }
+            let _ = self.len.compare_exchange(len, len + 1, Ordering::SeqCst, Ordering::SeqCst);
+        }
+    }
+}
+
+impl<T> Default for AppendVec<T> {
+    fn default() -> Self {
+        AppendVec::new()
+    }
+}
+
+impl<T> Drop for AppendVec<T> {
+    fn drop(&mut self) {
+        for (bucket, p) in self.buckets.iter_mut().enumerate() {
+            let p = *p.get_mut();
+            if p.is_null() {
+                continue;
+            }
+            unsafe {
+                let slots = Box::from_raw(ptr::slice_from_raw_parts_mut(p, bucket_len(bucket)));
+                for slot in &slots[..] {
+                    if *slot.ready.as_ptr() {
+                        ptr::drop_in_place((*slot.value.get()).as_mut_ptr());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: Debug> Debug for AppendVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries((0..self.len()).map(|i| unsafe { self.xget_unchecked(i) })).finish()
+    }
+}
+
+unsafe impl<T> Trustworthy for AppendVec<T> {
+    type Item = T;
+    fn base_len(&self) -> usize { self.len() }
+}
+
+unsafe impl<T> GetUnchecked for AppendVec<T> {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        &*(*self.slot(i).value.get()).as_ptr()
+    }
+}
+
+unsafe impl<T> GetUncheckedMut for AppendVec<T> {
+    unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+        &mut *(*self.slot(i).value.get()).as_mut_ptr()
+    }
+}
+
+impl<'id, T, M> Container<'id, &AppendVec<T>, M> {
+    /// Append `element` to the vector and return its index.
+    ///
+    /// This takes `&self`, so it can be called from several threads at the
+    /// same time, and the index is valid for the rest of the scope.
+    ///
+    /// **Blocks** until the elements that other threads started pushing
+    /// before this one are pushed too, since the index must be less than the
+    /// length. Use `AppendVec::push` for an unbranded index without waiting.
+    #[inline]
+    pub fn push(&self, element: T) -> Index<'id> {
+        let i = self.array().push(element);
+        // the length is at least `i + 1` once `i` is written, when
+        // the elements before it are written too; wait for them
+        while self.array().len() <= i {
+            std::hint::spin_loop();
+        }
+        unsafe {
+            Index::new(i)
+        }
+    }
+}
+
+#[cfg(feature = "use_std")]
+#[test]
+fn test_append_vec() {
+    use std::thread;
+    use crate::scope;
+
+    let data = AppendVec::new();
+    scope(&data, |v| {
+        let v = &v;
+        let indices = thread::scope(|s| {
+            let threads = (0..4).map(|t| s.spawn(move || {
+                (0..100).map(|j| (t * 100 + j, v.push(t * 100 + j))).collect::<Vec<_>>()
+            })).collect::<Vec<_>>();
+            threads.into_iter().flat_map(|t| t.join().unwrap()).collect::<Vec<_>>()
+        });
+        for (x, i) in indices {
+            assert_eq!(v[i], x);
+        }
+        assert_eq!(v.len(), 400);
+    });
+    let sum: i32 = (0..data.len()).map(|i| data.get(i).unwrap()).sum();
+    assert_eq!(sum, (0..400).sum());
+    assert!(data.get(400).is_none());
+}
@@ -0,0 +1,211 @@
+//! A bit vector, packed into words, where each index addresses one bit.
+
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+
+use crate::container::Container;
+use crate::container_traits::*;
+use crate::{ContainerPrivate, Index, Range};
+
+const WORD_BITS: usize = 64;
+
+/// A bit vector of `len` bits, packed into the words of `S`.
+///
+/// Bit `i` is bit `i % 64` of word `i / 64`, counting from the least
+/// significant bit. Its items are `bool`, so it can't be indexed by reference;
+/// in a `Container`, use `.get()`, `.set()` and `.toggle()` instead.
+///
+/// ```
+/// use indexing::scope;
+/// use indexing::bits::Bits;
+///
+/// let mut words = [0u64; 2];
+/// scope(Bits::new(&mut words[..], 100), |mut bits| {
+///     for i in bits.range() {
+///         if i.integer() % 3 == 0 {
+///             bits.set(i, true);
+///         }
+///     }
+///     assert_eq!(bits.count_ones(bits.range()), 34);
+/// });
+/// ```
+#[derive(Copy, Clone)]
+pub struct Bits<S> {
+    words: S,
+    len: usize,
+}
+
+impl<S> Bits<S>
+    where S: Trustworthy<Item=u64>,
+{
+    /// Create a bit vector of `len` bits, stored in `words`.
+    ///
+    /// ***Panics*** if `words` has fewer than `len` bits.
+    pub fn new(words: S, len: usize) -> Self {
+        assert!(len <= words.base_len().saturating_mul(WORD_BITS),
+                "Bits::new: not enough words for the length");
+        Bits { words, len }
+    }
+
+    /// Return the underlying words.
+    pub fn into_inner(self) -> S {
+        self.words
+    }
+}
+
+impl<S> Bits<S>
+    where S: GetUnchecked<Item=u64>,
+{
+    // Read `n` bits starting at bit `pos`, where `0 < n <= 64`.
+    // `pos + n` must be at most `len`.
+    #[inline]
+    unsafe fn read_bits(&self, pos: usize, n: usize) -> u64 {
+        let (w, off) = (pos / WORD_BITS, pos % WORD_BITS);
+        let mut x = *self.words.xget_unchecked(w) >> off;
+        if off != 0 && off + n > WORD_BITS {
+            x |= *self.words.xget_unchecked(w + 1) << (WORD_BITS - off);
+        }
+        if n < WORD_BITS {
+            x &= (1 << n) - 1;
+        }
+        x
+    }
+}
+
+impl<S> Debug for Bits<S>
+    where S: GetUnchecked<Item=u64>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut list = f.debug_list();
+        for i in 0..self.len {
+            list.entry(&unsafe { self.xget_value_unchecked(i) });
+        }
+        list.finish()
+    }
+}
+
+unsafe impl<S> Trustworthy for Bits<S> {
+    type Item = bool;
+    fn base_len(&self) -> usize { self.len }
+}
+
+unsafe impl<S> GetValueUnchecked for Bits<S>
+    where S: GetUnchecked<Item=u64>,
+{
+    unsafe fn xget_value_unchecked(&self, i: usize) -> bool {
+        *self.words.xget_unchecked(i / WORD_BITS) & (1 << (i % WORD_BITS)) != 0
+    }
+}
+
+unsafe impl<S> SetValueUnchecked for Bits<S>
+    where S: GetUncheckedMut<Item=u64>,
+{
+    unsafe fn xset_value_unchecked(&mut self, i: usize, value: bool) {
+        let word = self.words.xget_unchecked_mut(i / WORD_BITS);
+        let mask = 1 << (i % WORD_BITS);
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+}
+
+unsafe impl<S> FixedLength for Bits<S>
+    where S: FixedLength,
+{ }
+
+impl<'id, S, M> Container<'id, Bits<S>, M>
+    where S: GetUnchecked<Item=u64>,
+{
+    /// Flip the bit at `index`.
+    #[inline]
+    pub fn toggle(&mut self, index: Index<'id>)
+        where S: GetUncheckedMut
+    {
+        let i = index.index;
+        unsafe {
+            *self.array_mut().words.xget_unchecked_mut(i / WORD_BITS) ^= 1 << (i % WORD_BITS);
+        }
+    }
+
+    /// Return an iterator of the bits in `r`, 64 at a time.
+    ///
+    /// Each word holds the next 64 bits of the range, starting with its
+    /// least significant bit; the last word holds the rest of the bits, and
+    /// its unused high bits are zero.
+    #[inline]
+    pub fn words<P>(&self, r: Range<'id, P>) -> Words<'_, S> {
+        Words {
+            bits: self.array(),
+            start: r.start,
+            end: r.end,
+        }
+    }
+
+    /// Return the number of bits in `r` that are set.
+    pub fn count_ones<P>(&self, r: Range<'id, P>) -> usize {
+        self.words(r).map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+/// An iterator of the bits of a range of a `Bits` container, as words.
+///
+/// See `Container::words` for more information.
+pub struct Words<'a, S: 'a> {
+    bits: &'a Bits<S>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, S> Iterator for Words<'a, S>
+    where S: GetUnchecked<Item=u64>,
+{
+    type Item = u64;
+    #[inline]
+    fn next(&mut self) -> Option<u64> {
+        if self.start == self.end {
+            return None;
+        }
+        let n = Ord::min(self.end - self.start, WORD_BITS);
+        let word = unsafe { self.bits.read_bits(self.start, n) };
+        self.start += n;
+        Some(word)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = (self.end - self.start).div_ceil(WORD_BITS);
+        (n, Some(n))
+    }
+}
+
+impl<'a, S> ExactSizeIterator for Words<'a, S>
+    where S: GetUnchecked<Item=u64>,
+{ }
+
+impl<'a, S> FusedIterator for Words<'a, S>
+    where S: GetUnchecked<Item=u64>,
+{ }
+
+#[test]
+fn test_bits() {
+    use crate::scope;
+
+    let mut words = [!0u64, 0, 1];
+    scope(Bits::new(&mut words[..], 130), |mut b| {
+        let i = b.vet(64).unwrap();
+        assert!(!b.get(i));
+        b.set(i, true);
+        b.toggle(b.vet(0).unwrap());
+        assert!(b.get(i));
+        assert!(b.vet(130).is_err());
+
+        assert_eq!(b.count_ones(b.range()), 65);
+        let r = b.vet_range(60..129).unwrap();
+        assert!(b.words(r).eq([0b11111, 0b10000]));
+        assert_eq!(b.count_ones(r), 6);
+        assert_eq!(b.words(b.empty_range()).len(), 0);
+    });
+    assert_eq!(words, [!1, 1, 1]);
+}
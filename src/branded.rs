@@ -0,0 +1,139 @@
+//! An owner of a container and of state with indices into it, that can be
+//! stored in a struct.
+
+use crate::container::{Container, OnlyIndex};
+use crate::container_traits::*;
+use crate::fresh_brand;
+
+/// A family of state types `State<'id>`, that can hold indices and ranges
+/// with the brand `'id`.
+///
+/// ```
+/// use indexing::Index;
+/// use indexing::branded::StateFamily;
+///
+/// // the state of a parser: the position of the last token it read
+/// struct Cursor;
+///
+/// impl StateFamily for Cursor {
+///     type State<'id> = Option<Index<'id>>;
+/// }
+/// ```
+pub trait StateFamily {
+    type State<'id>;
+}
+
+impl StateFamily for () {
+    type State<'id> = ();
+}
+
+/// An owner of a container together with state that can hold indices and
+/// ranges into it.
+///
+/// The brand of the container and the state is re-opened on each call to
+/// `.with()`, where they are passed to a closure that works for any `'id`;
+/// so indices stored in the state stay valid between calls. The container is
+/// in `OnlyIndex` mode and can grow, but it can't shrink.
+///
+/// ```
+/// use indexing::Index;
+/// use indexing::branded::{Branded, StateFamily};
+/// use indexing::inline_vec::InlineVec;
+///
+/// struct Last;
+/// impl StateFamily for Last {
+///     type State<'id> = Option<Index<'id>>;
+/// }
+///
+/// let mut log = Branded::<InlineVec<&str, 4>, Last>::new(InlineVec::new(), |_| None);
+/// for msg in ["a", "b"] {
+///     log.with(|v, last| {
+///         *last = Some(v.push(msg));
+///     });
+/// }
+/// let last = log.with(|v, last| last.map(|i| v[i]));
+/// assert_eq!(last, Some("b"));
+/// ```
+pub struct Branded<A, S: StateFamily> {
+    // The brand is 'static here, but it is only exposed through closures
+    // that must work for any 'id, so it is unique in each of them.
+    container: Container<'static, A, OnlyIndex>,
+    state: S::State<'static>,
+}
+
+impl<A, S> Branded<A, S>
+    where A: Trustworthy,
+          S: StateFamily,
+{
+    /// Create a new owner of `arr`, where the initial state is returned
+    /// by `init`.
+    pub fn new<F>(arr: A, init: F) -> Self
+        where F: for<'id> FnOnce(&mut Container<'id, A, OnlyIndex>) -> S::State<'id>,
+    {
+        let mut container = unsafe { Container::new_unchecked(fresh_brand(), arr) };
+        let state = init(&mut container);
+        Branded { container, state }
+    }
+
+    /// Access the container and the state.
+    pub fn with<F, Out>(&mut self, f: F) -> Out
+        where F: for<'id> FnOnce(&mut Container<'id, A, OnlyIndex>, &mut S::State<'id>) -> Out,
+    {
+        f(&mut self.container, &mut self.state)
+    }
+
+    /// Return the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.container.len()
+    }
+
+    /// Return `true` if the container has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.container.is_empty()
+    }
+
+    /// Return the underlying array, discarding the state.
+    pub fn into_inner(self) -> A {
+        self.container.into_inner()
+    }
+}
+
+#[test]
+fn test_branded() {
+    use crate::inline_vec::InlineVec;
+    use crate::Index;
+
+    struct Max;
+    impl StateFamily for Max {
+        type State<'id> = Option<Index<'id>>;
+    }
+
+    struct Parser {
+        values: Branded<InlineVec<i32, 8>, Max>,
+    }
+
+    impl Parser {
+        fn feed(&mut self, x: i32) {
+            self.values.with(|v, max| {
+                let i = v.push(x);
+                if !matches!(*max, Some(m) if v[m] >= x) {
+                    *max = Some(i);
+                }
+            });
+        }
+        fn max(&mut self) -> Option<i32> {
+            self.values.with(|v, max| max.map(|m| v[m]))
+        }
+    }
+
+    let mut p = Parser { values: Branded::new(InlineVec::new(), |_| None) };
+    assert_eq!(p.max(), None);
+    for x in [3, 7, 2] {
+        p.feed(x);
+    }
+    assert_eq!(p.max(), Some(7));
+    assert_eq!(p.values.len(), 3);
+    assert_eq!(&p.values.into_inner()[..], &[3, 7, 2]);
+}
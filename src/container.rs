@@ -1,8 +1,11 @@
 
+use std::cell::Cell;
 use std::cmp;
 use std::ops;
 use std::ptr;
 use std::mem;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 use std::fmt::{self, Debug};
 
@@ -14,8 +17,9 @@ use crate::proof::*;
 use std;
 
 use crate::container_traits::*;
+use crate::slice_backed::{Sliced, SlicedMut};
 use crate::indexing::{IntoCheckedRange};
-use crate::{Id, Index, Range};
+use crate::{fresh_brand, Id, Index, Range, RawIndex, RawRange};
 use crate::ContainerPrivate;
 
 /// A branded container, that allows access only to indices and ranges with
@@ -72,6 +76,12 @@ impl<'id, Array, Mode> Clone for Container<'id, Array, Mode>
     }
 }
 
+/// A container can be copied if the inner array can (typically a shared
+/// slice).
+impl<'id, Array, Mode> Copy for Container<'id, Array, Mode>
+    where Array: Copy + FixedLength
+{ }
+
 impl<'id, Array, Mode> ContainerPrivate for Container<'id, Array, Mode> {
     type Array = Array;
     #[inline(always)]
@@ -92,6 +102,29 @@ impl<'id, Array, T, Mode> Container<'id, Array, Mode>
         self.arr.base_len()
     }
 
+    /// Return `true` if the container has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Create a container with any brand.
+    ///
+    /// # Safety
+    ///
+    /// The brand `id` must not be shared with any other container that it
+    /// can be used together with.
+    #[inline]
+    pub(crate) unsafe fn new_unchecked(id: Id<'id>, arr: Array) -> Self {
+        Container { id, arr, mode: PhantomData }
+    }
+
+    /// Return the underlying array, consuming the container.
+    #[inline]
+    pub(crate) fn into_inner(self) -> Array {
+        self.arr
+    }
+
     /// Convert the container into an only-indexing container.
     ///
     /// The container no longer allows pointer access. This unlocks
@@ -104,6 +137,45 @@ impl<'id, Array, T, Mode> Container<'id, Array, Mode>
         }
     }
 
+    /// Vet all the raw indices in `raw`, and return them as a slice of
+    /// indices; the slice is reinterpreted in place, not copied.
+    ///
+    /// Return an error if any index is out of bounds. The check is a single
+    /// pass over the indices, without early exit, so that it vectorizes.
+    ///
+    /// ```
+    /// use indexing::{scope, RawIndex};
+    ///
+    /// let data = [1, 2, 3, 4];
+    /// let raw = scope(&data[..], |v| {
+    ///     [v.vet(0).unwrap(), v.vet(2).unwrap()].map(RawIndex::from)
+    /// });
+    /// scope(&data[..], |v| {
+    ///     let indices = v.revet_all(&raw).unwrap();
+    ///     assert!(indices.iter().map(|&i| v[i]).eq([1, 3]));
+    /// });
+    /// scope(&data[..3], |v| {
+    ///     assert!(v.revet_all(&[RawIndex::new(3)]).is_err());
+    /// });
+    /// ```
+    #[inline]
+    pub fn revet_all<'a>(&self, raw: &'a [RawIndex]) -> Result<&'a [Index<'id>], IndexingError> {
+        let max = raw.iter().fold(0, |max, i| cmp::max(max, i.integer()));
+        if !raw.is_empty() && max >= self.len() {
+            return Err(index_error());
+        }
+        // Index is repr(transparent) over the index, like RawIndex
+        unsafe {
+            Ok(&*(raw as *const [RawIndex] as *const [Index<'id>]))
+        }
+    }
+
+    /// Vet the raw range `r`.
+    #[inline]
+    pub fn revet_range(&self, r: RawRange) -> Result<Range<'id>, IndexingError> {
+        self.vet_range(r.integers())
+    }
+
     /// Return the range [0, 0)
     #[inline]
     pub fn empty_range(&self) -> Range<'id> {
@@ -255,11 +327,11 @@ impl<'id, Array, T, Mode> Container<'id, Array, Mode>
     #[inline]
     pub fn scan_from<'b, F>(&'b self, index: Index<'id>, mut f: F) -> Range<'id, NonEmpty>
         where F: FnMut(&'b T) -> bool, T: 'b,
-              Array: Contiguous<Item=T>,
+              Array: GetUnchecked<Item=T>,
     {
         let mut end = index;
-        for elt in &self[self.after(index)] {
-            if !f(elt) {
+        for i in self.after(index) {
+            if !f(&self[i]) {
                 break;
             }
             end.index += 1;
@@ -278,12 +350,12 @@ impl<'id, Array, T, Mode> Container<'id, Array, Mode>
     #[inline]
     pub fn scan_from_rev<'b, F>(&'b self, index: Index<'id>, mut f: F) -> Range<'id, NonEmpty>
         where F: FnMut(&'b T) -> bool, T: 'b,
-              Array: Contiguous<Item=T>,
+              Array: GetUnchecked<Item=T>,
     {
         unsafe {
             let mut start = index;
-            for elt in self[..index].iter().rev() {
-                if !f(elt) {
+            for i in self.before(index).into_iter().rev() {
+                if !f(&self[i]) {
                     break;
                 }
                 start.index -= 1;
@@ -299,11 +371,11 @@ impl<'id, Array, T, Mode> Container<'id, Array, Mode>
     pub fn scan_range<'b, F, P>(&'b self, range: Range<'id, P>, mut f: F)
         -> (Range<'id>, Range<'id>)
         where F: FnMut(&'b T) -> bool, T: 'b,
-              Array: Contiguous<Item=T>,
+              Array: GetUnchecked<Item=T>,
     {
         let mut end = range.start;
-        for elt in &self[range] {
-            if !f(elt) {
+        for i in range {
+            if !f(&self[i]) {
                 break;
             }
             end += 1;
@@ -321,12 +393,35 @@ impl<'id, Array, T, Mode> Container<'id, Array, Mode>
     {
         unsafe {
             let self_mut = self as *mut Self;
-            let pi: *mut _ = &mut (*self_mut)[i];
-            let pj: *mut _ = &mut (*self_mut)[j];
+            let pi: *mut _ = &mut (&mut *self_mut)[i];
+            let pj: *mut _ = &mut (&mut *self_mut)[j];
             ptr::swap(pi, pj);
         }
     }
 
+    /// Return the element at `index` by value.
+    ///
+    /// This is for containers that can't be indexed by reference, like
+    /// a bit vector.
+    #[inline]
+    pub fn get(&self, index: Index<'id>) -> T
+        where Array: GetValueUnchecked
+    {
+        unsafe {
+            self.arr.xget_value_unchecked(index.index)
+        }
+    }
+
+    /// Set the element at `index` to `value`.
+    #[inline]
+    pub fn set(&mut self, index: Index<'id>, value: T)
+        where Array: SetValueUnchecked
+    {
+        unsafe {
+            self.arr.xset_value_unchecked(index.index, value)
+        }
+    }
+
     /// Rotate elements in the range `r` by one step to the right (towards higher indices)
     #[inline]
     pub fn rotate1_up<R>(&mut self, r: R)
@@ -380,13 +475,54 @@ impl<'id, Array, T, Mode> Container<'id, Array, Mode>
         if r.end <= s.start {
             let self_mut = self as *mut Self;
             unsafe {
-                Ok((&mut (*self_mut)[r], &mut (*self_mut)[s]))
+                Ok((&mut (&mut *self_mut)[r], &mut (&mut *self_mut)[s]))
             }
         } else {
             Err(index_error())
         }
     }
 
+    /// Return the elements in the range `r` as a pair of slices, for a
+    /// container that is made up of two contiguous parts.
+    ///
+    /// The elements of the range are the first slice followed by the second;
+    /// either may be empty.
+    #[inline]
+    pub fn range_slices<P>(&self, r: Range<'id, P>) -> (&[T], &[T])
+        where Array: ContiguousPair
+    {
+        let (a, b) = self.arr.as_slices();
+        let mid = a.len();
+        unsafe {
+            if r.end <= mid {
+                (a.get_unchecked(r.start..r.end), &[])
+            } else if r.start >= mid {
+                (b.get_unchecked(r.start - mid..r.end - mid), &[])
+            } else {
+                (a.get_unchecked(r.start..), b.get_unchecked(..r.end - mid))
+            }
+        }
+    }
+
+    /// Return the elements in the range `r` as a pair of mutable slices, for
+    /// a container that is made up of two contiguous parts.
+    #[inline]
+    pub fn range_slices_mut<P>(&mut self, r: Range<'id, P>) -> (&mut [T], &mut [T])
+        where Array: ContiguousPairMut
+    {
+        let (a, b) = self.arr.as_mut_slices();
+        let mid = a.len();
+        unsafe {
+            if r.end <= mid {
+                (a.get_unchecked_mut(r.start..r.end), &mut [])
+            } else if r.start >= mid {
+                (b.get_unchecked_mut(r.start - mid..r.end - mid), &mut [])
+            } else {
+                (a.get_unchecked_mut(r.start..), b.get_unchecked_mut(..r.end - mid))
+            }
+        }
+    }
+
     /// Zip by raw pointer (will be indentical if ranges have same starting point)
     pub fn zip_mut_raw<P, Q, F>(&mut self, r: Range<'id, P>, s: Range<'id, Q>, mut f: F)
         where F: FnMut(*mut T, *mut T),
@@ -420,6 +556,31 @@ impl<'id, Array, T> Container<'id, Array, OnlyIndex>
         }
     }
 
+    /// Add one element to the underlying storage if there is room for it,
+    /// and return its index; otherwise return the element.
+    ///
+    /// All outstanding indices remain valid, only the length of the
+    /// container is now larger.
+    pub fn try_push(&mut self, element: T) -> Result<Index<'id>, T> {
+        let i = self.arr.try_push(element)?;
+        debug_assert!(i < self.arr.base_len());
+        unsafe {
+            Ok(Index::new(i))
+        }
+    }
+
+    /// Add one element to the front of the underlying storage, and return
+    /// its index.
+    ///
+    /// All outstanding indices remain valid (in bounds), but elements have
+    /// shifted.
+    pub fn push_front(&mut self, element: T) -> Index<'id> {
+        unsafe {
+            self.arr.insert_unchecked(0, element);
+            Index::new(0)
+        }
+    }
+
     /// Insert one element in the underlying storage at `index`.
     ///
     /// All outstanding indices remain valid (in bounds), but elements have
@@ -432,6 +593,169 @@ impl<'id, Array, T> Container<'id, Array, OnlyIndex>
     }
 }
 
+#[cfg(feature = "alloc")]
+macro_rules! shrink_impl {
+    ($(#[$attr:meta])* [$($parm:tt)*] $array:ty) => {
+        $(#[$attr])*
+        impl<'id, $($parm)*> Container<'id, $array, OnlyIndex> {
+            /// Shorten the vector to `len` elements, if it is longer.
+            pub fn truncate<F, Out>(mut self, len: usize, f: F) -> Out
+                where F: for<'new> FnOnce(Container<'new, $array, OnlyIndex>, Remap<'id, 'new>) -> Out,
+            {
+                self.vec().truncate(len);
+                self.rebrand(Shift::None, f)
+            }
+
+            /// Remove all elements of the vector.
+            pub fn clear<F, Out>(mut self, f: F) -> Out
+                where F: for<'new> FnOnce(Container<'new, $array, OnlyIndex>, Remap<'id, 'new>) -> Out,
+            {
+                self.vec().clear();
+                self.rebrand(Shift::None, f)
+            }
+
+            /// Remove the last element of the vector, and pass it to the
+            /// continuation (or `None` if the vector was empty).
+            pub fn pop<F, Out>(mut self, f: F) -> Out
+                where F: for<'new> FnOnce(Container<'new, $array, OnlyIndex>, Remap<'id, 'new>, Option<T>) -> Out,
+            {
+                let elt = self.vec().pop();
+                self.rebrand(Shift::None, move |v, remap| f(v, remap, elt))
+            }
+
+            /// Remove the element at `index` and pass it to the continuation; the
+            /// last element of the vector takes its place.
+            pub fn swap_remove<F, Out>(mut self, index: Index<'id>, f: F) -> Out
+                where F: for<'new> FnOnce(Container<'new, $array, OnlyIndex>, Remap<'id, 'new>, T) -> Out,
+            {
+                let last = self.len() - 1;
+                let elt = self.vec().swap_remove(index.index);
+                self.rebrand(Shift::Moved(last, index.index), move |v, remap| f(v, remap, elt))
+            }
+
+            fn vec(&mut self) -> &mut Vec<T> {
+                &mut self.arr
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'id, Array> Container<'id, Array, OnlyIndex>
+    where Array: Trustworthy,
+{
+    /// Pass the container, which has shrunk, to `f` with a new brand, and
+    /// the map from its old indices to the new ones; `shift` says how the
+    /// remaining elements moved.
+    pub(crate) fn rebrand<F, Out>(self, shift: Shift, f: F) -> Out
+        where F: for<'new> FnOnce(Container<'new, Array, OnlyIndex>, Remap<'id, 'new>) -> Out,
+    {
+        let new = unsafe { fresh_brand() };
+        let remap = Remap {
+            old: self.id,
+            new,
+            len: self.len(),
+            shift,
+        };
+        f(unsafe { Container::new_unchecked(new, self.arr) }, remap)
+    }
+}
+
+#[cfg(feature = "alloc")]
+shrink_impl!(
+    /// Methods that shrink a vector, in only index mode.
+    ///
+    /// Shrinking would invalidate outstanding indices, so these methods consume
+    /// the container, and pass the shrunk vector to a continuation as a
+    /// container with a new brand `'new`; the `Remap` maps the old indices to
+    /// the new ones.
+    ///
+    /// ```
+    /// use indexing::scope;
+    ///
+    /// let mut v = vec![1, 2, 3, 4];
+    /// scope(&mut v, |v| {
+    ///     let v = v.only_index();
+    ///     let first = v.vet(0).unwrap();
+    ///     let last = v.vet(3).unwrap();
+    ///     v.swap_remove(first, |v, remap, removed| {
+    ///         assert_eq!(removed, 1);
+    ///         assert!(remap.index(first).is_none());
+    ///         assert_eq!(v[remap.index(last).unwrap()], 4);
+    ///     })
+    /// });
+    /// assert_eq!(v, [4, 2, 3]);
+    /// ```
+    [T] Vec<T>
+);
+#[cfg(feature = "alloc")]
+shrink_impl!(
+    /// Methods that shrink a vector, in only index mode; see the methods
+    /// for `Vec<T>`.
+    ['a, T] &'a mut Vec<T>
+);
+
+/// A map from the indices of a container, to the indices of the same
+/// container after it has shrunk and got the new brand `'new`.
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone, Debug)]
+pub struct Remap<'id, 'new> {
+    #[allow(dead_code)]
+    old: Id<'id>,
+    #[allow(dead_code)]
+    new: Id<'new>,
+    len: usize,
+    shift: Shift,
+}
+
+/// How the elements that remain moved when a container shrunk.
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Shift {
+    /// The elements stayed in place.
+    None,
+    /// The element at the first index moved to the second index, replacing
+    /// the element that was removed there.
+    Moved(usize, usize),
+    /// The element at this index was removed, and the elements after it
+    /// moved down one step.
+    Removed(usize),
+}
+
+#[cfg(feature = "alloc")]
+impl<'id, 'new> Remap<'id, 'new> {
+    /// Return the new index of the element at `index`, or `None` if it was
+    /// removed.
+    #[inline]
+    pub fn index(&self, index: Index<'id>) -> Option<Index<'new>> {
+        let mut i = index.index;
+        match self.shift {
+            Shift::None => { }
+            Shift::Moved(from, to) => {
+                if i == to {
+                    return None;
+                } else if i == from {
+                    i = to;
+                }
+            }
+            Shift::Removed(removed) => {
+                if i == removed {
+                    return None;
+                } else if i > removed {
+                    i -= 1;
+                }
+            }
+        }
+        if i < self.len {
+            unsafe {
+                Some(Index::new(i))
+            }
+        } else {
+            None
+        }
+    }
+}
+
 impl<'id, Array, T, Mode> Container<'id, Array, Mode>
     where Array: Trustworthy<Item=T> + FixedLength
 {
@@ -442,13 +766,147 @@ impl<'id, Array, T, Mode> Container<'id, Array, Mode>
     ///
     /// The twin container is OnlyIndex-marked, because only indices/index
     /// ranges transfer between twins, and branded raw pointers of course not.
+    ///
+    /// Strings can't be twins: only the length is checked, and a
+    /// `CharBoundary` range of one string is not a char boundary range of
+    /// another.
+    ///
+    /// ```compile_fail
+    /// use indexing::scope;
+    ///
+    /// scope("aé", |s| {
+    ///     let r = s.vet_char_range(0..1).unwrap();
+    ///     let t = s.make_twin("éa").unwrap();
+    ///     &t[r]
+    /// });
+    /// ```
     pub fn make_twin<Array2>(&self, arr: Array2) -> Result<Container<'id, Array2, OnlyIndex>, IndexingError>
         where Array2: Trustworthy + FixedLength
     {
         if self.len() != arr.base_len() {
             Err(index_error())
         } else {
-            Ok(Container { id: self.id, arr: arr, mode: PhantomData })
+            Ok(Container { id: self.id, arr, mode: PhantomData })
+        }
+    }
+}
+
+/// Methods specific to containers of a length known at compile time
+impl<'id, Array, Mode> Container<'id, Array, Mode>
+    where Array: ConstLength,
+{
+    /// Return the index `I`, which is checked to be in bounds at compile
+    /// time.
+    ///
+    /// ```compile_fail
+    /// use indexing::scope_array;
+    ///
+    /// scope_array([0; 4], |v| {
+    ///     v.index::<4>();  //~ ERROR: index out of bounds
+    /// });
+    /// ```
+    #[inline]
+    pub fn index<const I: usize>(&self) -> Index<'id> {
+        const { assert!(I < Array::LEN, "index out of bounds") };
+        unsafe {
+            Index::new(I)
+        }
+    }
+}
+
+/// Methods specific to string containers
+impl<'id, Array, Mode> Container<'id, Array, Mode>
+    where Array: Utf8,
+{
+    /// Return the full range of the string; its endpoints are char
+    /// boundaries.
+    #[inline]
+    pub fn char_range(&self) -> Range<'id, CharBoundary> {
+        unsafe {
+            Range::from_any(0, self.len())
+        }
+    }
+
+    /// Vet the absolute `index` as a char boundary.
+    ///
+    /// The end of the string is a char boundary too, so the index may be
+    /// an edge index.
+    #[inline]
+    pub fn vet_char_boundary(&self, index: usize) -> Result<Index<'id, CharBoundary>, IndexingError> {
+        if self.arr.as_str().is_char_boundary(index) {
+            unsafe {
+                Ok(Index::new(index))
+            }
+        } else {
+            Err(index_error())
+        }
+    }
+
+    /// Vet the range `r`, which must start and end on char boundaries.
+    #[inline]
+    pub fn vet_char_range(&self, r: ops::Range<usize>) -> Result<Range<'id, CharBoundary>, IndexingError> {
+        let s = self.arr.as_str();
+        if r.start <= r.end && s.is_char_boundary(r.start) && s.is_char_boundary(r.end) {
+            unsafe {
+                Ok(Range::from_any(r.start, r.end))
+            }
+        } else {
+            Err(index_error())
+        }
+    }
+
+    /// Split the string at the char boundary `index`.
+    #[inline]
+    pub fn split_at_char(&self, index: Index<'id, CharBoundary>)
+        -> (Range<'id, CharBoundary>, Range<'id, CharBoundary>)
+    {
+        unsafe {
+            (Range::from_any(0, index.index), Range::from_any(index.index, self.len()))
+        }
+    }
+
+    /// Return the first char in the range `r`, or `None` if it is empty.
+    #[inline]
+    pub fn first_char(&self, r: Range<'id, CharBoundary>) -> Option<char> {
+        if r.start >= r.end {
+            return None;
+        }
+        // r.start is a char boundary in front of the char boundary r.end,
+        // so a whole char in valid UTF-8 starts there; decode it without
+        // checks.
+        let bytes = self.arr.as_str().as_bytes();
+        unsafe {
+            let byte = |i: usize| *bytes.get_unchecked(r.start + i) as u32;
+            let cont = |i: usize| byte(i) & 0x3f;
+            let b0 = byte(0);
+            let c = if b0 < 0x80 {
+                b0
+            } else if b0 < 0xe0 {
+                (b0 & 0x1f) << 6 | cont(1)
+            } else if b0 < 0xf0 {
+                (b0 & 0x0f) << 12 | cont(1) << 6 | cont(2)
+            } else {
+                (b0 & 0x07) << 18 | cont(1) << 12 | cont(2) << 6 | cont(3)
+            };
+            Some(char::from_u32_unchecked(c))
+        }
+    }
+
+    /// Return the first char in the range `r` and the range of the rest of
+    /// the chars, or `None` if it is empty.
+    #[inline]
+    pub fn split_first_char(&self, r: Range<'id, CharBoundary>)
+        -> Option<(char, Range<'id, CharBoundary>)>
+    {
+        self.first_char(r).map(|c| unsafe {
+            (c, Range::from_any(r.start + c.len_utf8(), r.end))
+        })
+    }
+
+    #[inline]
+    fn str_range(&self, r: Range<'id, CharBoundary>) -> &str {
+        unsafe {
+            self.arr.as_str().get_unchecked(r.start..r.end)
         }
     }
 }
@@ -487,7 +945,7 @@ impl<'id, T, Array, P, M> ops::Index<Range<'id, P>> for Container<'id, Array, M>
     fn index(&self, r: Range<'id, P>) -> &Self::Output {
         unsafe {
             std::slice::from_raw_parts(
-                self.arr.begin().offset(r.start as isize),
+                self.arr.begin().add(r.start),
                 r.len())
         }
     }
@@ -501,12 +959,40 @@ impl<'id, Array, P, M> ops::IndexMut<Range<'id, P>> for Container<'id, Array, M>
     fn index_mut(&mut self, r: Range<'id, P>) -> &mut Self::Output {
         unsafe {
             std::slice::from_raw_parts_mut(
-                self.arr.begin_mut().offset(r.start as isize),
+                self.arr.begin_mut().add(r.start),
                 r.len())
         }
     }
 }
 
+/// `&self[r]` where `r` is a `Range<'id, CharBoundary>` of a string.
+impl<'id, M> ops::Index<Range<'id, CharBoundary>> for Container<'id, &str, M> {
+    type Output = str;
+    #[inline(always)]
+    fn index(&self, r: Range<'id, CharBoundary>) -> &str {
+        self.str_range(r)
+    }
+}
+
+/// `&self[r]` where `r` is a `Range<'id, CharBoundary>` of a string.
+impl<'id, M> ops::Index<Range<'id, CharBoundary>> for Container<'id, &mut str, M> {
+    type Output = str;
+    #[inline(always)]
+    fn index(&self, r: Range<'id, CharBoundary>) -> &str {
+        self.str_range(r)
+    }
+}
+
+/// `&mut self[r]` where `r` is a `Range<'id, CharBoundary>` of a string.
+impl<'id, M> ops::IndexMut<Range<'id, CharBoundary>> for Container<'id, &mut str, M> {
+    #[inline(always)]
+    fn index_mut(&mut self, r: Range<'id, CharBoundary>) -> &mut str {
+        unsafe {
+            self.arr.get_unchecked_mut(r.start..r.end)
+        }
+    }
+}
+
 /// `&self[i..]` where `i` is an `Index<'id, P>` which may be an edge index.
 impl<'id, T, P, Array, M> ops::Index<ops::RangeFrom<Index<'id, P>>> for Container<'id, Array, M>
     where Array: Contiguous<Item=T>,
@@ -517,7 +1003,7 @@ impl<'id, T, P, Array, M> ops::Index<ops::RangeFrom<Index<'id, P>>> for Containe
         let i = r.start.index;
         unsafe {
             std::slice::from_raw_parts(
-                self.arr.begin().offset(i as isize),
+                self.arr.begin().add(i),
                 self.len() - i)
         }
     }
@@ -532,7 +1018,7 @@ impl<'id, T, P, Array, M> ops::IndexMut<ops::RangeFrom<Index<'id, P>>> for Conta
         let i = r.start.index;
         unsafe {
             std::slice::from_raw_parts_mut(
-                self.arr.begin_mut().offset(i as isize),
+                self.arr.begin_mut().add(i),
                 self.len() - i)
         }
     }
@@ -666,7 +1152,409 @@ pub fn scope<Array, F, Out>(arr: Array, f: F) -> Out
     // to somehow bind the lifetime to the inside of this function, making
     // it sound again. Borrowck will never do such analysis, so we don't
     // care.
-    f(Container { id: Id::default(), arr: arr, mode: PhantomData })
+    f(Container { id: Id::default(), arr, mode: PhantomData })
+}
+
+/// Create an indexing scope for a container, where the scope is an async
+/// closure.
+///
+/// This is like `scope`, but the container and its indices and ranges can
+/// be held across `.await` points in the closure. The brand works the same
+/// way: indices and ranges branded with `'id` can not leave the closure's
+/// future.
+///
+/// The returned future is `Send` if the closure's future is, which is when
+/// everything it holds across an `.await` is `Send`, including the container
+/// (which is `Send` when `Array` is).
+///
+/// Requires the crate feature `async` (and Rust 1.85, for async closures).
+///
+/// ```
+/// use indexing::scope_async;
+///
+/// async fn next_chunk() -> usize { 2 }
+///
+/// async fn checksum(body: &[u8]) -> u32 {
+///     scope_async(body, async |b| {
+///         let mut sum = 0;
+///         let mut rest = b.range();
+///         while !rest.is_empty() {
+///             // hold a branded range across an await point
+///             let n = next_chunk().await;
+///             let (chunk, tail, _) = rest.split_at(n);
+///             sum += b[chunk].iter().map(|&x| x as u32).sum::<u32>();
+///             rest = tail;
+///         }
+///         sum
+///     }).await
+/// }
+/// # fn block_on<F: std::future::Future>(f: F) -> F::Output {
+/// #     let mut f = std::pin::pin!(f);
+/// #     let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+/// #     loop {
+/// #         if let std::task::Poll::Ready(x) = f.as_mut().poll(&mut cx) {
+/// #             return x;
+/// #         }
+/// #     }
+/// # }
+/// assert_eq!(block_on(checksum(&[1, 2, 3, 4, 5])), 15);
+///
+/// // the future is Send
+/// fn assert_send<T: Send>(_: &T) { }
+/// let mut data = [3, 1, 2];
+/// let fut = scope_async(&mut data[..], async |mut v| {
+///     let r = v.range().nonempty().unwrap();
+///     next_chunk().await;
+///     v.swap(r.first(), r.last());
+/// });
+/// assert_send(&fut);
+/// block_on(fut);
+/// assert_eq!(data, [2, 1, 3]);
+/// ```
+#[cfg(feature = "async")]
+pub async fn scope_async<Array, F, Out>(arr: Array, f: F) -> Out
+    where F: for<'id> AsyncFnOnce(Container<'id, Array>) -> Out,
+          Array: Trustworthy,
+{
+    f(Container { id: unsafe { fresh_brand() }, arr, mode: PhantomData }).await
+}
+
+/// Return the common length of `lens`, if they are all equal.
+pub(crate) fn common_len(lens: &[usize]) -> Option<usize> {
+    let (&first, rest) = lens.split_first()?;
+    if rest.iter().all(|&len| len == first) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+mod private {
+    pub trait Sealed { }
+}
+
+/// A tuple of arrays that can be opened together with `scope_twins`.
+///
+/// It is implemented for tuples of two to twelve arrays, that are
+/// `Trustworthy` and `FixedLength`. The trait is sealed: it can't be
+/// implemented outside this crate.
+pub trait Twins : private::Sealed {
+    /// The tuple of containers, one for each array, branded with `'id`.
+    type Containers<'id>;
+
+    /// Return the length of each array, if they are all equal.
+    fn base_len(&self) -> Option<usize>;
+
+    /// # Safety
+    ///
+    /// The arrays must have equal lengths, and the containers' brand must be
+    /// used like the brand from `fresh_brand`.
+    unsafe fn into_containers<'id>(self) -> Self::Containers<'id>;
+}
+
+macro_rules! twins_impl {
+    ($($A:ident $i:tt),+) => {
+        impl<$($A),+> private::Sealed for ($($A,)+)
+            where $($A: Trustworthy + FixedLength),+
+        { }
+
+        impl<$($A),+> Twins for ($($A,)+)
+            where $($A: Trustworthy + FixedLength),+
+        {
+            type Containers<'id> = ($(Container<'id, $A, OnlyIndex>,)+);
+
+            fn base_len(&self) -> Option<usize> {
+                common_len(&[$(self.$i.base_len()),+])
+            }
+
+            unsafe fn into_containers<'id>(self) -> Self::Containers<'id> {
+                let id = fresh_brand();
+                ($(Container::new_unchecked(id, self.$i),)+)
+            }
+        }
+    }
+}
+
+twins_impl!(A 0, B 1);
+twins_impl!(A 0, B 1, C 2);
+twins_impl!(A 0, B 1, C 2, D 3);
+twins_impl!(A 0, B 1, C 2, D 3, E 4);
+twins_impl!(A 0, B 1, C 2, D 3, E 4, F 5);
+twins_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+twins_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+twins_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+twins_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+twins_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+twins_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+/// Create an indexing scope for several arrays of equal length, that share
+/// one brand.
+///
+/// The closure is passed a tuple of containers, one for each array in the
+/// tuple `arrays`, and their indices and ranges are valid for all of them.
+/// Like twins from `Container::make_twin`, the containers are `OnlyIndex`.
+///
+/// Return an error if the arrays don't all have the same length.
+///
+/// ```
+/// use indexing::scope_twins;
+///
+/// let xs = [1, 2, 3];
+/// let ys = [4, 5, 6];
+/// let mut sums = [0; 3];
+/// scope_twins((&xs[..], &ys[..], &mut sums[..]), |(xs, ys, mut sums)| {
+///     for i in xs.range() {
+///         sums[i] = xs[i] + ys[i];
+///     }
+/// }).unwrap();
+/// assert_eq!(sums, [5, 7, 9]);
+///
+/// assert!(scope_twins((&xs[..], &ys[1..]), |_| ()).is_err());
+/// ```
+///
+/// Strings can't be twins, for the same reason as in `make_twin`:
+///
+/// ```compile_fail
+/// use indexing::scope_twins;
+///
+/// scope_twins(("aé", "éa"), |(a, b)| {
+///     let r = a.vet_char_range(1..3).unwrap();
+///     &b[r]
+/// }).unwrap();
+/// ```
+pub fn scope_twins<Arrays, F, Out>(arrays: Arrays, f: F) -> Result<Out, IndexingError>
+    where F: for<'id> FnOnce(Arrays::Containers<'id>) -> Out,
+          Arrays: Twins,
+{
+    if arrays.base_len().is_none() {
+        return Err(index_error());
+    }
+    unsafe {
+        Ok(f(arrays.into_containers()))
+    }
+}
+
+/// Create an indexing scope for a container of a length known at compile
+/// time, like an array `[T; N]`.
+///
+/// This is like `scope`, and the container can in addition produce indices
+/// that are checked at compile time using `Container::index`.
+pub fn scope_array<Array, F, Out>(arr: Array, f: F) -> Out
+    where F: for<'id> FnOnce(Container<'id, Array>) -> Out,
+          Array: ConstLength,
+{
+    scope(arr, f)
+}
+
+/// Create an indexing scope for a collection that implements `SliceBacked`.
+///
+/// The collection's slice is taken once, and the container borrows it for
+/// the whole scope.
+pub fn scope_slice<'a, S, F, Out>(data: &'a S, f: F) -> Out
+    where F: for<'id> FnOnce(Container<'id, Sliced<'a, S>>) -> Out,
+          S: ?Sized + SliceBacked,
+{
+    scope(Sliced::new(data), f)
+}
+
+/// Create an indexing scope for a collection that implements
+/// `SliceBackedMut`.
+///
+/// The collection's mutable slice is taken once, and the container borrows
+/// it for the whole scope.
+pub fn scope_slice_mut<'a, S, F, Out>(data: &'a mut S, f: F) -> Out
+    where F: for<'id> FnOnce(Container<'id, SlicedMut<'a, S>>) -> Out,
+          S: ?Sized + SliceBackedMut,
+{
+    scope(SlicedMut::new(data), f)
+}
+
+/// Create an indexing scope for a mutable slice, viewed as a slice of
+/// `Cell`s.
+///
+/// The container is `Copy`, so that it can be shared freely, and elements
+/// can be read and written through any copy of it.
+///
+/// ```
+/// use indexing::scope_cells;
+///
+/// let mut dist = [0, 5, 1];
+/// scope_cells(&mut dist[..], |d| {
+///     let alias = d;
+///     let r = d.range().nonempty().unwrap();
+///     let (a, b) = (r.first(), r.last());
+///     d[a].set(d[b].get() + 1);
+///     alias[a].swap(&alias[b]);
+///     assert_eq!(d[b].replace(7), 2);
+/// });
+/// assert_eq!(dist, [1, 5, 7]);
+/// ```
+pub fn scope_cells<'a, T, F, Out>(data: &'a mut [T], f: F) -> Out
+    where F: for<'id> FnOnce(Container<'id, &'a [Cell<T>]>) -> Out,
+{
+    scope(Cell::from_mut(data).as_slice_of_cells(), f)
+}
+
+/// Create a branded container in the current scope, without a closure.
+///
+/// `make_container!(let c = arr;)` binds `c` to a `Container` of `arr` with
+/// a fresh brand, like the argument of the closure of `scope(arr, |c| ..)`.
+/// The container lives until the end of the enclosing block, and code that
+/// uses it can use `?`, `return` and `break` like any other code.
+///
+/// Use `make_container!(let mut c = arr;)` for a mutable binding.
+///
+/// ```
+/// use indexing::{make_container, IndexingError};
+///
+/// fn swap_ends(v: &mut [i32]) -> Result<(), IndexingError> {
+///     make_container!(let mut c = v;);
+///     let r = c.range().nonempty()?;
+///     c.swap(r.first(), r.last());
+///     Ok(())
+/// }
+///
+/// let mut data = [1, 2, 3];
+/// swap_ends(&mut data).unwrap();
+/// assert_eq!(data, [3, 2, 1]);
+/// assert!(swap_ends(&mut []).is_err());
+/// ```
+///
+/// Each container has its own brand, so indices don't transfer between them:
+///
+/// ```compile_fail
+/// use indexing::make_container;
+///
+/// let (v, w) = ([1, 2, 3], [4, 5, 6]);
+/// make_container!(let a = &v[..];);
+/// make_container!(let b = &w[..];);
+/// let i = a.vet(0).unwrap();
+/// let _ = b[i];
+/// ```
+///
+/// The expression is evaluated as safe code, so it can't do anything that
+/// needs `unsafe`:
+///
+/// ```compile_fail
+/// use indexing::make_container;
+///
+/// let data = [1, 2, 3];
+/// let ptr = &data[..] as *const [i32];
+/// make_container!(let c = &*ptr;);
+/// ```
+///
+/// The brand is generated using the drop guard technique: the container is
+/// branded with the lifetime of a borrow of a local variable, which an
+/// invariant guard with a `Drop` impl keeps alive to the end of the block.
+#[macro_export]
+macro_rules! make_container {
+    (let $name:ident = $arr:expr;) => {
+        // evaluate the expression outside of the unsafe blocks
+        let arr = $arr;
+        let tag = unsafe { $crate::__private::Brand::new() };
+        let _guard;
+        let $name = unsafe { $crate::__private::make_container(arr, tag) };
+        _guard = unsafe { $crate::__private::BrandGuard::new(&tag) };
+    };
+    (let mut $name:ident = $arr:expr;) => {
+        // evaluate the expression outside of the unsafe blocks
+        let arr = $arr;
+        let tag = unsafe { $crate::__private::Brand::new() };
+        let _guard;
+        let mut $name = unsafe { $crate::__private::make_container(arr, tag) };
+        _guard = unsafe { $crate::__private::BrandGuard::new(&tag) };
+    };
+}
+
+/// An invariant brand for `make_container!`.
+#[doc(hidden)]
+#[derive(Copy, Clone)]
+pub struct Brand<'id> {
+    id: Id<'id>,
+}
+
+impl<'id> Brand<'id> {
+    /// # Safety
+    ///
+    /// The brand must be guarded by a `BrandGuard` in the same block, which
+    /// makes `'id` unique.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub unsafe fn new() -> Self {
+        Brand { id: Id::default() }
+    }
+}
+
+/// Keeps a `Brand` borrowed until the end of its block.
+#[doc(hidden)]
+pub struct BrandGuard<'id> {
+    life: PhantomData<&'id Brand<'id>>,
+}
+
+impl<'id> BrandGuard<'id> {
+    /// # Safety
+    ///
+    /// Only for use in `make_container!`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub unsafe fn new(_brand: &'id Brand<'id>) -> Self {
+        BrandGuard { life: PhantomData }
+    }
+}
+
+// The guard must have a drop impl, so that the borrow of its brand lasts
+// until it is dropped at the end of the block.
+impl<'id> Drop for BrandGuard<'id> {
+    #[inline(always)]
+    fn drop(&mut self) { }
+}
+
+/// # Safety
+///
+/// Only for use in `make_container!`.
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn make_container<'id, Array>(arr: Array, brand: Brand<'id>) -> Container<'id, Array>
+    where Array: Trustworthy,
+{
+    Container { id: brand.id, arr, mode: PhantomData }
+}
+
+#[test]
+fn test_scope_twins() {
+    let keys = [3, 1, 2, 0];
+    let mut a = [0; 4];
+    let mut b = [0.; 4];
+    let mut c = [' '; 4];
+    scope_twins((&keys[..], &mut a[..], &mut b[..], &mut c[..]), |(k, mut a, mut b, mut c)| {
+        for i in k.range() {
+            a[i] = k[i] * 10;
+            b[i] = k[i] as f64 / 2.;
+            c[i] = (b'a' + k[i] as u8) as char;
+        }
+    }).unwrap();
+    assert_eq!(a, [30, 10, 20, 0]);
+    assert_eq!(b, [1.5, 0.5, 1., 0.]);
+    assert_eq!(c, ['d', 'b', 'c', 'a']);
+    assert!(scope_twins((&keys[..], &a[..3], &b[..]), |_| ()).is_err());
+}
+
+#[test]
+fn test_revet() {
+    let data = [0; 8];
+    let (raw_range, raw) = scope(&data[..], |v| {
+        let (_, b, _) = v.range().split_at(5);
+        (RawRange::from(b), [RawIndex::from(b.nonempty().unwrap().last()), RawIndex::new(0)])
+    });
+    scope(&data[..], |v| {
+        assert_eq!(v.revet_range(raw_range).unwrap().len(), 3);
+        assert_eq!(v.revet_all(&raw).unwrap()[0].integer(), 7);
+        assert!(v.revet_all(&[]).unwrap().is_empty());
+    });
+    scope(&data[..7], |v| {
+        assert!(v.revet_range(raw_range).is_err());
+        assert!(v.revet_all(&raw).is_err());
+    });
 }
 
 #[test]
@@ -754,11 +1642,57 @@ fn test_contains() {
     });
 }
 
+#[test]
+fn test_array() {
+    let mut data = [1, 2, 3, 4];
+    scope_array(&mut data, |mut v| {
+        let i = v.index::<0>();
+        let j = v.index::<3>();
+        v.swap(i, j);
+        assert_eq!(v.range().len(), 4);
+        for elt in &mut v[..] {
+            *elt *= 2;
+        }
+    });
+    assert_eq!(data, [8, 4, 6, 2]);
+}
+
+#[test]
+fn test_str() {
+    let text = "aä€b";
+    scope(text, |s| {
+        assert!(s.vet_char_boundary(2).is_err());
+        assert!(s.vet_char_range(0..2).is_err());
+        let r = s.vet_char_range(1..6).unwrap();
+        assert_eq!(&s[r], "ä€");
+        assert_eq!(s.first_char(r), Some('ä'));
+        let (c, rest) = s.split_first_char(r).unwrap();
+        assert_eq!(c, 'ä');
+        assert_eq!(&s[rest], "€");
+
+        let i = s.vet_char_boundary(s.len()).unwrap();
+        let (a, b) = s.split_at_char(i);
+        assert_eq!(&s[a], text);
+        assert_eq!(s.first_char(b), None);
+    });
+
+    let text = "zß€😀";
+    scope(text, |s| {
+        let mut r = s.char_range();
+        let mut chars = text.chars();
+        while let Some((c, rest)) = s.split_first_char(r) {
+            assert_eq!(Some(c), chars.next());
+            r = rest;
+        }
+        assert_eq!(chars.next(), None);
+    });
+}
+
 #[test]
 fn test_is_send_sync() {
     fn _is_send_sync<T: Send + Sync>() { }
 
-    fn _test<'id>() {
+    fn _test<'id>(_: Id<'id>) {
         _is_send_sync::<Id<'id>>();
         _is_send_sync::<Index<'id>>();
         _is_send_sync::<Range<'id>>();
@@ -1,36 +1,149 @@
 
 /// The most basic container trait: it can have indices and ranges that are
 /// trusted to be in bounds.
+///
+/// # Safety
+///
+/// `base_len` must report the number of elements in the container; indices
+/// below it are trusted to be in bounds.
 pub unsafe trait Trustworthy {
     type Item;
     fn base_len(&self) -> usize;
 }
 
 /// The container has a contiguous addressable range.
+///
+/// # Safety
+///
+/// `begin()..end()` must be a valid range of `base_len()` elements.
 pub unsafe trait Contiguous : Trustworthy {
     fn begin(&self) -> *const Self::Item;
     fn end(&self) -> *const Self::Item;
     fn as_slice(&self) -> &[Self::Item];
 }
 
+/// The container's elements can be accessed by index without bounds checks.
+///
+/// # Safety
+///
+/// `xget_unchecked` must return the element at `i` for every `i` less than
+/// `base_len()`.
 pub unsafe trait GetUnchecked : Trustworthy {
+    /// # Safety
+    ///
+    /// `i` must be less than `base_len()`.
     unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item;
 }
 
+/// The container's elements can be mutated by index without bounds checks.
+///
+/// # Safety
+///
+/// Same as for `GetUnchecked`, and distinct `i` must give distinct elements.
 pub unsafe trait GetUncheckedMut : GetUnchecked {
+    /// # Safety
+    ///
+    /// `i` must be less than `base_len()`.
     unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item;
 }
 
+/// The container's elements can be read by index without bounds checks,
+/// returning them by value.
+///
+/// This is for containers that don't store their elements as separate
+/// values, so that they can't return a reference (like a bit vector).
+///
+/// # Safety
+///
+/// `xget_value_unchecked` must be safe to call for every `i` less than
+/// `base_len()`.
+pub unsafe trait GetValueUnchecked : Trustworthy {
+    /// # Safety
+    ///
+    /// `i` must be less than `base_len()`.
+    unsafe fn xget_value_unchecked(&self, i: usize) -> Self::Item;
+}
+
+/// The container's elements can be written by index without bounds checks.
+///
+/// # Safety
+///
+/// Same as for `GetValueUnchecked`.
+pub unsafe trait SetValueUnchecked : GetValueUnchecked {
+    /// # Safety
+    ///
+    /// `i` must be less than `base_len()`.
+    unsafe fn xset_value_unchecked(&mut self, i: usize, value: Self::Item);
+}
+
+/// The container has a contiguous, mutable addressable range.
+///
+/// # Safety
+///
+/// Same as for `Contiguous`.
 pub unsafe trait ContiguousMut : Contiguous {
     fn begin_mut(&mut self) -> *mut Self::Item;
     fn end_mut(&mut self) -> *mut Self::Item;
     fn as_mut_slice(&mut self) -> &mut [Self::Item];
 }
 
+/// The container is made up of two contiguous parts, which hold the elements
+/// in order when taken one after the other (like a ring buffer).
+///
+/// # Safety
+///
+/// The two slices must together have `base_len()` elements.
+pub unsafe trait ContiguousPair : Trustworthy {
+    fn as_slices(&self) -> (&[Self::Item], &[Self::Item]);
+}
+
+/// The container is made up of two contiguous, mutable parts.
+///
+/// # Safety
+///
+/// Same as for `ContiguousPair`.
+pub unsafe trait ContiguousPairMut : ContiguousPair {
+    fn as_mut_slices(&mut self) -> (&mut [Self::Item], &mut [Self::Item]);
+}
+
+/// The container's length is known at compile time.
+///
+/// # Safety
+///
+/// `base_len` must always return `LEN`.
+pub unsafe trait ConstLength : FixedLength {
+    const LEN: usize;
+}
+
+/// The container is a string slice; its items are the bytes of the string.
+///
+/// # Safety
+///
+/// `as_str` must return the string whose bytes the container's indices refer
+/// to. The container must not be `FixedLength`, because twin containers only
+/// check the length, and `CharBoundary` indices depend on the contents.
+pub unsafe trait Utf8 : Trustworthy<Item=u8> {
+    fn as_str(&self) -> &str;
+}
+
+/// The container is a mutable string slice.
+///
+/// # Safety
+///
+/// Same as for `Utf8`.
+pub unsafe trait Utf8Mut : Utf8 {
+    fn as_mut_str(&mut self) -> &mut str;
+}
+
 /// The container does not change is length while we are trusting it
+///
+/// # Safety
+///
+/// `base_len` must return the same value for as long as the container
+/// is in scope.
 pub unsafe trait FixedLength : Trustworthy { }
 
-unsafe impl<'a, C: ?Sized> Trustworthy for &'a C
+unsafe impl<C: ?Sized> Trustworthy for &C
     where C: Trustworthy
 {
     type Item = C::Item;
@@ -39,7 +152,7 @@ unsafe impl<'a, C: ?Sized> Trustworthy for &'a C
     }
 }
 
-unsafe impl<'a, C: ?Sized> Trustworthy for &'a mut C
+unsafe impl<C: ?Sized> Trustworthy for &mut C
     where C: Trustworthy
 {
     type Item = C::Item;
@@ -48,7 +161,7 @@ unsafe impl<'a, C: ?Sized> Trustworthy for &'a mut C
     }
 }
 
-unsafe impl<'a, C: ?Sized> ContiguousMut for &'a mut C
+unsafe impl<C: ?Sized> ContiguousMut for &mut C
     where C: ContiguousMut
 {
     fn begin_mut(&mut self) -> *mut Self::Item { (**self).begin_mut() }
@@ -58,7 +171,7 @@ unsafe impl<'a, C: ?Sized> ContiguousMut for &'a mut C
     }
 }
 
-unsafe impl<'a, C: ?Sized> GetUnchecked for &'a C
+unsafe impl<C: ?Sized> GetUnchecked for &C
     where C: GetUnchecked
 {
     unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
@@ -66,7 +179,7 @@ unsafe impl<'a, C: ?Sized> GetUnchecked for &'a C
     }
 }
 
-unsafe impl<'a, C: ?Sized> GetUnchecked for &'a mut C
+unsafe impl<C: ?Sized> GetUnchecked for &mut C
     where C: GetUnchecked
 {
     unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
@@ -74,7 +187,7 @@ unsafe impl<'a, C: ?Sized> GetUnchecked for &'a mut C
     }
 }
 
-unsafe impl<'a, C: ?Sized> GetUncheckedMut for &'a mut C
+unsafe impl<C: ?Sized> GetUncheckedMut for &mut C
     where C: GetUncheckedMut
 {
     unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
@@ -82,7 +195,31 @@ unsafe impl<'a, C: ?Sized> GetUncheckedMut for &'a mut C
     }
 }
 
-unsafe impl<'a, C: ?Sized> Contiguous for &'a C
+unsafe impl<C: ?Sized> GetValueUnchecked for &C
+    where C: GetValueUnchecked
+{
+    unsafe fn xget_value_unchecked(&self, i: usize) -> Self::Item {
+        (**self).xget_value_unchecked(i)
+    }
+}
+
+unsafe impl<C: ?Sized> GetValueUnchecked for &mut C
+    where C: GetValueUnchecked
+{
+    unsafe fn xget_value_unchecked(&self, i: usize) -> Self::Item {
+        (**self).xget_value_unchecked(i)
+    }
+}
+
+unsafe impl<C: ?Sized> SetValueUnchecked for &mut C
+    where C: SetValueUnchecked
+{
+    unsafe fn xset_value_unchecked(&mut self, i: usize, value: Self::Item) {
+        (**self).xset_value_unchecked(i, value)
+    }
+}
+
+unsafe impl<C: ?Sized> Contiguous for &C
     where C: Contiguous,
 {
     fn begin(&self) -> *const Self::Item {
@@ -96,7 +233,7 @@ unsafe impl<'a, C: ?Sized> Contiguous for &'a C
     }
 }
 
-unsafe impl<'a, C: ?Sized> Contiguous for &'a mut C
+unsafe impl<C: ?Sized> Contiguous for &mut C
     where C: Contiguous,
 {
     fn begin(&self) -> *const Self::Item {
@@ -110,6 +247,30 @@ unsafe impl<'a, C: ?Sized> Contiguous for &'a mut C
     }
 }
 
+unsafe impl<C: ?Sized> ContiguousPair for &C
+    where C: ContiguousPair,
+{
+    fn as_slices(&self) -> (&[Self::Item], &[Self::Item]) {
+        (**self).as_slices()
+    }
+}
+
+unsafe impl<C: ?Sized> ContiguousPair for &mut C
+    where C: ContiguousPair,
+{
+    fn as_slices(&self) -> (&[Self::Item], &[Self::Item]) {
+        (**self).as_slices()
+    }
+}
+
+unsafe impl<C: ?Sized> ContiguousPairMut for &mut C
+    where C: ContiguousPairMut,
+{
+    fn as_mut_slices(&mut self) -> (&mut [Self::Item], &mut [Self::Item]) {
+        (**self).as_mut_slices()
+    }
+}
+
 unsafe impl<T> Trustworthy for [T] {
     type Item = T;
     fn base_len(&self) -> usize { self.len() }
@@ -155,21 +316,132 @@ unsafe impl<T> Contiguous for [T] {
     }
 }
 
-unsafe impl<'a, C: ?Sized> FixedLength for &'a C
+unsafe impl<C: ?Sized> ConstLength for &C
+    where C: ConstLength
+{
+    const LEN: usize = C::LEN;
+}
+
+unsafe impl<C: ?Sized> ConstLength for &mut C
+    where C: ConstLength
+{
+    const LEN: usize = C::LEN;
+}
+
+unsafe impl<C: ?Sized> Utf8 for &C
+    where C: Utf8,
+{
+    fn as_str(&self) -> &str {
+        (**self).as_str()
+    }
+}
+
+unsafe impl<C: ?Sized> Utf8 for &mut C
+    where C: Utf8,
+{
+    fn as_str(&self) -> &str {
+        (**self).as_str()
+    }
+}
+
+unsafe impl<C: ?Sized> Utf8Mut for &mut C
+    where C: Utf8Mut,
+{
+    fn as_mut_str(&mut self) -> &mut str {
+        (**self).as_mut_str()
+    }
+}
+
+// Note that there is no GetUncheckedMut, since writing to the bytes could
+// break the UTF-8 invariant.
+unsafe impl Trustworthy for str {
+    type Item = u8;
+    fn base_len(&self) -> usize { self.len() }
+}
+
+unsafe impl GetUnchecked for str {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        self.as_bytes().get_unchecked(i)
+    }
+}
+
+unsafe impl Utf8 for str {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+unsafe impl Utf8Mut for str {
+    fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+}
+
+unsafe impl<C: ?Sized> FixedLength for &C
     where C: FixedLength
 { }
 
-unsafe impl<'a, C: ?Sized> FixedLength for &'a mut C
+unsafe impl<C: ?Sized> FixedLength for &mut C
     where C: FixedLength
 { }
 
 // The slice stays the same length while it's in a container
 // (in contrast, the Vec does not)
 unsafe impl<T> FixedLength for [T] { }
+// but str is not FixedLength, see `Utf8`
+
+mod array_impls {
+    use super::*;
+
+    unsafe impl<T, const N: usize> Trustworthy for [T; N] {
+        type Item = T;
+        fn base_len(&self) -> usize { N }
+    }
+
+    unsafe impl<T, const N: usize> ContiguousMut for [T; N] {
+        fn begin_mut(&mut self) -> *mut Self::Item { self[..].begin_mut() }
+        fn end_mut(&mut self) -> *mut Self::Item { self[..].end_mut() }
+        fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+            self
+        }
+    }
 
-#[cfg(feature = "use_std")]
+    unsafe impl<T, const N: usize> GetUnchecked for [T; N] {
+        unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+            self.get_unchecked(i)
+        }
+    }
+
+    unsafe impl<T, const N: usize> GetUncheckedMut for [T; N] {
+        unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+            self.get_unchecked_mut(i)
+        }
+    }
+
+    unsafe impl<T, const N: usize> Contiguous for [T; N] {
+        fn begin(&self) -> *const Self::Item {
+            self[..].begin()
+        }
+        fn end(&self) -> *const Self::Item {
+            self[..].end()
+        }
+        fn as_slice(&self) -> &[Self::Item] {
+            self
+        }
+    }
+
+    unsafe impl<T, const N: usize> FixedLength for [T; N] { }
+
+    unsafe impl<T, const N: usize> ConstLength for [T; N] {
+        const LEN: usize = N;
+    }
+}
+
+#[cfg(feature = "alloc")]
 mod vec_impls {
     use super::*;
+    use alloc::vec::Vec;
+
     unsafe impl<T> Trustworthy for Vec<T> {
         type Item = T;
         fn base_len(&self) -> usize { self.len() }
@@ -219,24 +491,244 @@ mod vec_impls {
     }
 }
 
+#[cfg(feature = "alloc")]
+mod boxed_impls {
+    use super::*;
+    use alloc::boxed::Box;
+    use alloc::rc::Rc;
+    use alloc::sync::Arc;
+
+    unsafe impl<T> Trustworthy for Box<[T]> {
+        type Item = T;
+        fn base_len(&self) -> usize { self.len() }
+    }
+
+    unsafe impl<T> ContiguousMut for Box<[T]> {
+        fn begin_mut(&mut self) -> *mut Self::Item { (**self).begin_mut() }
+        fn end_mut(&mut self) -> *mut Self::Item { (**self).end_mut() }
+        fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+            self
+        }
+    }
+
+    unsafe impl<T> GetUnchecked for Box<[T]> {
+        unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+            self.get_unchecked(i)
+        }
+    }
+
+    unsafe impl<T> GetUncheckedMut for Box<[T]> {
+        unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+            self.get_unchecked_mut(i)
+        }
+    }
+
+    unsafe impl<T> Contiguous for Box<[T]> {
+        fn begin(&self) -> *const Self::Item {
+            (**self).begin()
+        }
+        fn end(&self) -> *const Self::Item {
+            (**self).end()
+        }
+        fn as_slice(&self) -> &[Self::Item] {
+            self
+        }
+    }
+
+    unsafe impl<T> FixedLength for Box<[T]> { }
+
+    // Rc and Arc are shared, so they only get the immutable traits
+
+    unsafe impl<T> Trustworthy for Rc<[T]> {
+        type Item = T;
+        fn base_len(&self) -> usize { self.len() }
+    }
+
+    unsafe impl<T> GetUnchecked for Rc<[T]> {
+        unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+            self.get_unchecked(i)
+        }
+    }
+
+    unsafe impl<T> Contiguous for Rc<[T]> {
+        fn begin(&self) -> *const Self::Item {
+            (**self).begin()
+        }
+        fn end(&self) -> *const Self::Item {
+            (**self).end()
+        }
+        fn as_slice(&self) -> &[Self::Item] {
+            self
+        }
+    }
+
+    unsafe impl<T> FixedLength for Rc<[T]> { }
+
+    unsafe impl<T> Trustworthy for Arc<[T]> {
+        type Item = T;
+        fn base_len(&self) -> usize { self.len() }
+    }
+
+    unsafe impl<T> GetUnchecked for Arc<[T]> {
+        unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+            self.get_unchecked(i)
+        }
+    }
+
+    unsafe impl<T> Contiguous for Arc<[T]> {
+        fn begin(&self) -> *const Self::Item {
+            (**self).begin()
+        }
+        fn end(&self) -> *const Self::Item {
+            (**self).end()
+        }
+        fn as_slice(&self) -> &[Self::Item] {
+            self
+        }
+    }
+
+    unsafe impl<T> FixedLength for Arc<[T]> { }
+}
+
+#[cfg(feature = "alloc")]
+mod vec_deque_impls {
+    use super::*;
+    use alloc::collections::VecDeque;
+
+    unsafe impl<T> Trustworthy for VecDeque<T> {
+        type Item = T;
+        fn base_len(&self) -> usize { self.len() }
+    }
+
+    unsafe impl<T> GetUnchecked for VecDeque<T> {
+        unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+            let (a, b) = self.as_slices();
+            if i < a.len() {
+                a.get_unchecked(i)
+            } else {
+                b.get_unchecked(i - a.len())
+            }
+        }
+    }
+
+    unsafe impl<T> GetUncheckedMut for VecDeque<T> {
+        unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+            let (a, b) = self.as_mut_slices();
+            if i < a.len() {
+                a.get_unchecked_mut(i)
+            } else {
+                b.get_unchecked_mut(i - a.len())
+            }
+        }
+    }
+
+    unsafe impl<T> ContiguousPair for VecDeque<T> {
+        fn as_slices(&self) -> (&[Self::Item], &[Self::Item]) {
+            self.as_slices()
+        }
+    }
+
+    unsafe impl<T> ContiguousPairMut for VecDeque<T> {
+        fn as_mut_slices(&mut self) -> (&mut [Self::Item], &mut [Self::Item]) {
+            self.as_mut_slices()
+        }
+    }
+
+    unsafe impl<T> Pushable for VecDeque<T> {
+        fn push(&mut self, item: T) -> usize {
+            let i = self.len();
+            self.push_back(item);
+            i
+        }
+        unsafe fn insert_unchecked(&mut self, index: usize, item: Self::Item) {
+            if index == 0 {
+                self.push_front(item)
+            } else {
+                self.insert(index, item)
+            }
+        }
+    }
+}
+
+/// The container can grow by adding elements; it never shrinks.
+///
+/// # Safety
+///
+/// `push` must return the index of the new element, and neither method may
+/// decrease `base_len`.
 pub unsafe trait Pushable : Trustworthy {
     fn push(&mut self, item: Self::Item) -> usize;
+    /// Push `item` if there is room for it, and return its index;
+    /// otherwise return `item`.
+    fn try_push(&mut self, item: Self::Item) -> Result<usize, Self::Item> {
+        Ok(self.push(item))
+    }
+    /// # Safety
+    ///
+    /// `index` must be less than or equal to `base_len()`.
     unsafe fn insert_unchecked(&mut self, index: usize, item: Self::Item);
 }
 
 
-unsafe impl<'a, C: ?Sized> Pushable for &'a mut C
+unsafe impl<C: ?Sized> Pushable for &mut C
     where C: Pushable,
 {
     fn push(&mut self, item: Self::Item) -> usize {
         (**self).push(item)
     }
+    fn try_push(&mut self, item: Self::Item) -> Result<usize, Self::Item> {
+        (**self).try_push(item)
+    }
     unsafe fn insert_unchecked(&mut self, index: usize, item: Self::Item) {
         (**self).insert_unchecked(index, item)
     }
 }
 
 
+/// A collection that can be viewed as a slice.
+///
+/// This trait is safe to implement, so a custom collection can be used in
+/// a scope without unsafe code: `scope_slice` takes the slice once when the
+/// scope is created, and its container, a `Sliced<S>`, implements the
+/// container traits using that slice. The container borrows the collection,
+/// so its length can not change during the scope.
+///
+/// The container traits are not implemented for every `SliceBacked` type
+/// directly: such blanket impls would overlap with the impls for other
+/// types, like `Vec<T>` and references, and `as_slice` is safe code that
+/// could return a slice of a different length each time it is called.
+///
+/// The macro `slice_backed!` implements the traits for a newtype.
+///
+/// ```
+/// use indexing::container_traits::SliceBacked;
+/// use indexing::scope_slice;
+///
+/// struct Buffer { data: Vec<u8>, used: usize }
+///
+/// impl SliceBacked for Buffer {
+///     type Item = u8;
+///     fn as_slice(&self) -> &[u8] { &self.data[..self.used] }
+/// }
+///
+/// let buf = Buffer { data: vec![1, 2, 3, 0], used: 3 };
+/// let sum = scope_slice(&buf, |v| {
+///     v.range().into_iter().map(|i| v[i] as u32).sum::<u32>()
+/// });
+/// assert_eq!(sum, 6);
+/// ```
+pub trait SliceBacked {
+    type Item;
+    fn as_slice(&self) -> &[Self::Item];
+}
+
+/// A collection that can be viewed as a mutable slice.
+///
+/// See `SliceBacked`; this trait is used by `scope_slice_mut`.
+pub trait SliceBackedMut : SliceBacked {
+    fn as_mut_slice(&mut self) -> &mut [Self::Item];
+}
+
 /// A range being `..`, `a..`, `..b`, or `a..b`.
 pub trait IndexRange<I> : Sized {
     fn start(&self) -> Option<I> { None }
@@ -258,7 +750,10 @@ impl<I: Copy> IndexRange<I> for Range<I> {
 }
 
 /// A range with at most one point, being `a..`, `..`, or `..b`.
-// unsafe because: trusted to have at one endpoint
+///
+/// # Safety
+///
+/// Trusted to have at most one endpoint.
 pub unsafe trait OnePointRange<I> : IndexRange<I> {
 }
 
@@ -0,0 +1,313 @@
+//! A gap buffer, for fast insertion near a cursor.
+
+use std::fmt::{self, Debug};
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+use std::slice;
+
+use alloc::vec::Vec;
+
+use crate::container::{Container, OnlyIndex, Remap, Shift};
+use crate::container_traits::*;
+use crate::{ContainerPrivate, Index};
+
+/// A gap buffer: a vector with a movable gap of free space at its cursor.
+///
+/// Inserting an element moves the gap to the insertion point, so a run of
+/// insertions close to each other (like typing in a text editor) is fast.
+/// Positions are logical, so they do not change when the gap moves.
+///
+/// As a `Pushable` container, it supports `.push()` and `.insert()` with
+/// branded indices in `OnlyIndex` mode. Removing an element shortens the
+/// container, so `.remove()` in `OnlyIndex` mode consumes the container and
+/// passes it on with a new brand.
+pub struct GapBuffer<T> {
+    buf: Vec<MaybeUninit<T>>,
+    gap_start: usize,
+    gap_end: usize,
+}
+
+impl<T> GapBuffer<T> {
+    /// Create a new empty gap buffer.
+    pub fn new() -> Self {
+        GapBuffer { buf: Vec::new(), gap_start: 0, gap_end: 0 }
+    }
+
+    /// Return the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len() - (self.gap_end - self.gap_start)
+    }
+
+    /// Return `true` if the buffer has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the position of the cursor (the gap).
+    #[inline]
+    pub fn cursor(&self) -> usize {
+        self.gap_start
+    }
+
+    /// Move the cursor (the gap) to the position `pos`.
+    ///
+    /// ***Panics*** if `pos` is greater than the length.
+    pub fn set_cursor(&mut self, pos: usize) {
+        assert!(pos <= self.len(), "GapBuffer::set_cursor: position out of bounds");
+        let p = self.buf.as_mut_ptr();
+        unsafe {
+            if pos < self.gap_start {
+                let n = self.gap_start - pos;
+                ptr::copy(p.add(pos), p.add(self.gap_end - n), n);
+                self.gap_start -= n;
+                self.gap_end -= n;
+            } else {
+                let n = pos - self.gap_start;
+                ptr::copy(p.add(self.gap_end), p.add(self.gap_start), n);
+                self.gap_start += n;
+                self.gap_end += n;
+            }
+        }
+    }
+
+    /// Insert `item` at the position `pos`, and move the cursor after it.
+    ///
+    /// ***Panics*** if `pos` is greater than the length.
+    pub fn insert(&mut self, pos: usize, item: T) {
+        self.set_cursor(pos);
+        if self.gap_start == self.gap_end {
+            self.grow();
+        }
+        self.buf[self.gap_start] = MaybeUninit::new(item);
+        self.gap_start += 1;
+    }
+
+    /// Append `item` to the end of the buffer.
+    pub fn push(&mut self, item: T) {
+        let len = self.len();
+        self.insert(len, item);
+    }
+
+    /// Remove the element at the position `pos` and return it; the cursor
+    /// is moved to `pos`.
+    ///
+    /// ***Panics*** if `pos` is out of bounds.
+    pub fn remove(&mut self, pos: usize) -> T {
+        assert!(pos < self.len(), "GapBuffer::remove: position out of bounds");
+        self.set_cursor(pos);
+        let item = unsafe { ptr::read(self.buf[self.gap_end].as_ptr()) };
+        self.gap_end += 1;
+        item
+    }
+
+    /// Return the element at the position `pos`, if it is in bounds.
+    #[inline]
+    pub fn get(&self, pos: usize) -> Option<&T> {
+        if pos < self.len() {
+            unsafe {
+                Some(self.xget_unchecked(pos))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Return the elements before and after the gap.
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let p = self.buf.as_ptr() as *const T;
+        unsafe {
+            (slice::from_raw_parts(p, self.gap_start),
+             slice::from_raw_parts(p.add(self.gap_end), self.buf.len() - self.gap_end))
+        }
+    }
+
+    /// Return the elements before and after the gap.
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let p = self.buf.as_mut_ptr() as *mut T;
+        unsafe {
+            (slice::from_raw_parts_mut(p, self.gap_start),
+             slice::from_raw_parts_mut(p.add(self.gap_end), self.buf.len() - self.gap_end))
+        }
+    }
+
+    #[inline]
+    fn physical_index(&self, pos: usize) -> usize {
+        if pos < self.gap_start { pos } else { pos + (self.gap_end - self.gap_start) }
+    }
+
+    // Grow the buffer, keeping the gap at the same position
+    #[cold]
+    fn grow(&mut self) {
+        let old_cap = self.buf.len();
+        let new_cap = if old_cap == 0 { 8 } else { old_cap * 2 };
+        let mut new_buf = Vec::with_capacity(new_cap);
+        new_buf.resize_with(new_cap, MaybeUninit::uninit);
+        let tail = old_cap - self.gap_end;
+        unsafe {
+            let src = self.buf.as_ptr();
+            let dst = new_buf.as_mut_ptr();
+            ptr::copy_nonoverlapping(src, dst, self.gap_start);
+            ptr::copy_nonoverlapping(src.add(self.gap_end), dst.add(new_cap - tail), tail);
+        }
+        self.gap_end = new_cap - tail;
+        // the old buffer is MaybeUninit, so it drops no elements
+        mem::swap(&mut self.buf, &mut new_buf);
+    }
+}
+
+impl<T> Default for GapBuffer<T> {
+    fn default() -> Self {
+        GapBuffer::new()
+    }
+}
+
+impl<T> Drop for GapBuffer<T> {
+    fn drop(&mut self) {
+        let (a, b) = self.as_mut_slices();
+        let (a, b) = (a as *mut [T], b as *mut [T]);
+        self.gap_start = 0;
+        self.gap_end = self.buf.len();
+        unsafe {
+            ptr::drop_in_place(a);
+            ptr::drop_in_place(b);
+        }
+    }
+}
+
+impl<T: Debug> Debug for GapBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (a, b) = self.as_slices();
+        f.debug_list().entries(a).entries(b).finish()
+    }
+}
+
+unsafe impl<T> Trustworthy for GapBuffer<T> {
+    type Item = T;
+    fn base_len(&self) -> usize { self.len() }
+}
+
+unsafe impl<T> GetUnchecked for GapBuffer<T> {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        &*self.buf.get_unchecked(self.physical_index(i)).as_ptr()
+    }
+}
+
+unsafe impl<T> GetUncheckedMut for GapBuffer<T> {
+    unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+        let j = self.physical_index(i);
+        &mut *self.buf.get_unchecked_mut(j).as_mut_ptr()
+    }
+}
+
+unsafe impl<T> ContiguousPair for GapBuffer<T> {
+    fn as_slices(&self) -> (&[Self::Item], &[Self::Item]) {
+        self.as_slices()
+    }
+}
+
+unsafe impl<T> ContiguousPairMut for GapBuffer<T> {
+    fn as_mut_slices(&mut self) -> (&mut [Self::Item], &mut [Self::Item]) {
+        self.as_mut_slices()
+    }
+}
+
+unsafe impl<T> Pushable for GapBuffer<T> {
+    fn push(&mut self, item: T) -> usize {
+        let i = self.len();
+        self.push(item);
+        i
+    }
+    unsafe fn insert_unchecked(&mut self, index: usize, item: Self::Item) {
+        self.insert(index, item)
+    }
+}
+
+macro_rules! remove_impl {
+    ($(#[$attr:meta])* [$($parm:tt)*] $array:ty) => {
+        $(#[$attr])*
+        impl<'id, $($parm)*> Container<'id, $array, OnlyIndex> {
+            /// Remove the element at `index` and pass it to the continuation;
+            /// the cursor moves to `index`, so removing next to the cursor
+            /// is fast.
+            pub fn remove<F, Out>(mut self, index: Index<'id>, f: F) -> Out
+                where F: for<'new> FnOnce(Container<'new, $array, OnlyIndex>, Remap<'id, 'new>, T) -> Out,
+            {
+                let elt = self.array_mut().remove(index.integer());
+                self.rebrand(Shift::Removed(index.integer()), move |v, remap| f(v, remap, elt))
+            }
+        }
+    }
+}
+
+remove_impl!(
+    /// Methods that shrink a gap buffer, in only index mode.
+    ///
+    /// Like the methods that shrink a `Vec`, they consume the container, and
+    /// pass the shrunk buffer to a continuation as a container with a new
+    /// brand `'new`; the `Remap` maps the old indices to the new ones.
+    ///
+    /// ```
+    /// use indexing::scope;
+    /// use indexing::gap_buffer::GapBuffer;
+    ///
+    /// let mut text = GapBuffer::new();
+    /// for c in "abcd".chars() {
+    ///     text.push(c);
+    /// }
+    /// scope(&mut text, |t| {
+    ///     let t = t.only_index();
+    ///     let b = t.vet(1).unwrap();
+    ///     let d = t.vet(3).unwrap();
+    ///     t.remove(b, |t, remap, removed| {
+    ///         assert_eq!(removed, 'b');
+    ///         assert!(remap.index(b).is_none());
+    ///         assert_eq!(t[remap.index(d).unwrap()], 'd');
+    ///     })
+    /// });
+    /// assert_eq!(text.cursor(), 1);
+    /// assert_eq!(text.as_slices(), (&['a'][..], &['c', 'd'][..]));
+    /// ```
+    [T] GapBuffer<T>
+);
+remove_impl!(
+    /// Methods that shrink a gap buffer, in only index mode; see the methods
+    /// for `GapBuffer<T>`.
+    ['a, T] &'a mut GapBuffer<T>
+);
+
+#[test]
+fn test_gap_buffer() {
+    use alloc::format;
+    use alloc::string::String;
+    use crate::scope;
+
+    let mut text = GapBuffer::new();
+    for c in "hello world".chars() {
+        text.push(c);
+    }
+    scope(&mut text, |t| {
+        let mut t = t.only_index();
+        let comma = t.vet(5).unwrap();
+        for c in ", dear".chars().rev() {
+            t.insert(comma, c);
+        }
+        assert_eq!(t[comma], ',');
+        assert_eq!(t[t.vet(16).unwrap()], 'd');
+        let end = t.push('!');
+        assert_eq!(t[end], '!');
+        let (a, b) = t.range_slices(t.range());
+        let s: String = a.iter().chain(b).collect();
+        assert_eq!(s, "hello, dear world!");
+    });
+    assert_eq!(text.remove(0), 'h');
+    text.set_cursor(text.len());
+    assert_eq!(text.get(0), Some(&'e'));
+    assert_eq!(text.len(), 17);
+    text.set_cursor(4);
+    let chars: Vec<char> = "ello, dear world!".chars().collect();
+    assert_eq!(format!("{:?}", text), format!("{:?}", chars));
+}
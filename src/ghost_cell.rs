@@ -0,0 +1,167 @@
+//! Branded tokens and cells for shared mutation without runtime borrow flags
+//! (“GhostCell”).
+
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+
+use crate::Id;
+
+/// A token that gives access to all the `GhostCell`s with the same brand.
+///
+/// The token can't be copied or cloned; a shared borrow of the token lets
+/// you read any of its cells, and a mutable borrow of the token lets you
+/// mutate one of them.
+pub struct GhostToken<'id> {
+    #[allow(dead_code)]
+    id: Id<'id>,
+}
+
+/// A cell that can only be accessed using the `GhostToken` with the same
+/// brand.
+///
+/// Many shared references to the cell can exist at the same time, and the
+/// borrow of the token decides if the cell can be read or written, so no
+/// runtime borrow flags are needed (like in `RefCell`).
+///
+/// ```
+/// use indexing::ghost_cell::{GhostCell, scope_ghost};
+///
+/// struct Node<'id> {
+///     value: GhostCell<'id, i32>,
+/// }
+///
+/// scope_ghost(|mut token| {
+///     let node = Node { value: GhostCell::new(1) };
+///     // a graph with several references to the same node
+///     let edges = [&node, &node];
+///     for n in edges {
+///         *n.value.borrow_mut(&mut token) += 10;
+///     }
+///     assert_eq!(*node.value.borrow(&token), 21);
+/// });
+/// ```
+///
+/// A cell can't be borrowed mutably while it is borrowed:
+///
+/// ```compile_fail
+/// use indexing::ghost_cell::{GhostCell, scope_ghost};
+///
+/// scope_ghost(|mut token| {
+///     let cell = GhostCell::new(1);
+///     let a = cell.borrow(&token);
+///     *cell.borrow_mut(&mut token) += 1;
+///     assert_eq!(*a, 1);
+/// });
+/// ```
+#[repr(transparent)]
+pub struct GhostCell<'id, T: ?Sized> {
+    #[allow(dead_code)]
+    id: Id<'id>,
+    value: UnsafeCell<T>,
+}
+
+/// Create a scope with a new `GhostToken`.
+///
+/// The token's brand `'id` is unique, like the brand of a container in
+/// `scope`, so the token only gives access to the cells that are created in
+/// this scope.
+pub fn scope_ghost<F, Out>(f: F) -> Out
+    where F: for<'id> FnOnce(GhostToken<'id>) -> Out,
+{
+    f(GhostToken { id: Id::default() })
+}
+
+// The token is only used to prove access, so these are the same bounds as
+// for `RwLock<T>`.
+unsafe impl<'id, T: ?Sized + Send> Send for GhostCell<'id, T> { }
+unsafe impl<'id, T: ?Sized + Send + Sync> Sync for GhostCell<'id, T> { }
+
+impl<'id, T> GhostCell<'id, T> {
+    /// Create a new cell containing `value`.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        GhostCell { id: Id { id: PhantomData }, value: UnsafeCell::new(value) }
+    }
+
+    /// Return the value of the cell.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<'id, T: ?Sized> GhostCell<'id, T> {
+    /// Return a reference to the value, borrowing the token.
+    #[inline]
+    pub fn borrow<'a>(&'a self, _token: &'a GhostToken<'id>) -> &'a T {
+        // The token is borrowed, so nothing can borrow a cell mutably
+        unsafe {
+            &*self.value.get()
+        }
+    }
+
+    /// Return a mutable reference to the value, borrowing the token
+    /// mutably.
+    #[inline]
+    pub fn borrow_mut<'a>(&'a self, _token: &'a mut GhostToken<'id>) -> &'a mut T {
+        // The token is borrowed mutably, so nothing else can borrow a cell
+        unsafe {
+            &mut *self.value.get()
+        }
+    }
+
+    /// Return a mutable reference to the value; the cell is not shared, so
+    /// the token is not needed.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Return a cell from a mutable reference.
+    #[inline]
+    pub fn from_mut(value: &mut T) -> &mut Self {
+        // GhostCell is repr(transparent) over UnsafeCell<T>, which has the
+        // same layout as T
+        unsafe {
+            &mut *(value as *mut T as *mut Self)
+        }
+    }
+}
+
+impl<'id, T: Default> Default for GhostCell<'id, T> {
+    fn default() -> Self {
+        GhostCell::new(T::default())
+    }
+}
+
+#[test]
+fn test_ghost_cell() {
+    struct Node<'a, 'id> {
+        value: GhostCell<'id, i32>,
+        next: Option<&'a Node<'a, 'id>>,
+    }
+
+    scope_ghost(|mut token| {
+        let c = Node { value: GhostCell::new(3), next: None };
+        let b = Node { value: GhostCell::new(2), next: Some(&c) };
+        let a = Node { value: GhostCell::new(1), next: Some(&b) };
+
+        let mut node = Some(&a);
+        while let Some(n) = node {
+            let v = *n.value.borrow(&token);
+            if let Some(next) = n.next {
+                *next.value.borrow_mut(&mut token) += v;
+            }
+            node = n.next;
+        }
+        assert_eq!(*c.value.borrow(&token), 6);
+    });
+
+    let mut x = 1;
+    scope_ghost(|token| {
+        let cell = GhostCell::from_mut(&mut x);
+        *cell.get_mut() = 2;
+        assert_eq!(*cell.borrow(&token), 2);
+    });
+    assert_eq!(x, 2);
+}
@@ -0,0 +1,354 @@
+//! A two-dimensional grid container with separately branded row and column
+//! indices.
+
+use std::ops;
+use std::slice;
+use std::marker::PhantomData;
+
+use crate::container_traits::*;
+use crate::index_error::{IndexingError, index_error};
+use crate::indexing::RangeIter;
+use crate::{Id, Index, Range};
+
+/// A branded row index of a `Grid`.
+pub type Row<'r> = Index<'r>;
+
+/// A branded column index of a `Grid`.
+pub type Col<'c> = Index<'c>;
+
+/// A branded grid, that stores its elements in row major order.
+///
+/// The grid is indexed like `self[(r, c)]` where `r` is a trusted row index
+/// and `c` is a trusted column index; each has its own brand. Indexing like
+/// this uses no runtime bounds checking.
+pub struct Grid<'r, 'c, Array> {
+    #[allow(dead_code)]
+    row_id: Id<'r>,
+    #[allow(dead_code)]
+    col_id: Id<'c>,
+    arr: Array,
+    nrows: usize,
+    ncols: usize,
+}
+
+/// Create an indexing scope for a grid of `nrows` by `ncols` elements,
+/// stored in row major order in `arr`.
+///
+/// The grid's row and column indices are branded with the unique lifetimes
+/// `'r` and `'c`.
+///
+/// Return an error if the length of `arr` is not `nrows * ncols`.
+pub fn scope_grid<Array, F, Out>(arr: Array, nrows: usize, ncols: usize, f: F)
+    -> Result<Out, IndexingError>
+    where F: for<'r, 'c> FnOnce(Grid<'r, 'c, Array>) -> Out,
+          Array: Trustworthy + FixedLength,
+{
+    if nrows.checked_mul(ncols) != Some(arr.base_len()) {
+        return Err(index_error());
+    }
+    Ok(f(Grid {
+        row_id: Id::default(),
+        col_id: Id::default(),
+        arr,
+        nrows,
+        ncols,
+    }))
+}
+
+impl<'r, 'c, Array, T> Grid<'r, 'c, Array>
+    where Array: Trustworthy<Item=T>,
+{
+    /// Return the number of rows.
+    #[inline]
+    pub fn nrows(&self) -> usize { self.nrows }
+
+    /// Return the number of columns.
+    #[inline]
+    pub fn ncols(&self) -> usize { self.ncols }
+
+    /// Return the range of all rows.
+    #[inline]
+    pub fn rows(&self) -> Range<'r> {
+        unsafe {
+            Range::from(0, self.nrows)
+        }
+    }
+
+    /// Return the range of all columns.
+    #[inline]
+    pub fn cols(&self) -> Range<'c> {
+        unsafe {
+            Range::from(0, self.ncols)
+        }
+    }
+
+    /// Vet the row index `r`.
+    #[inline]
+    pub fn vet_row(&self, r: usize) -> Result<Row<'r>, IndexingError> {
+        self.rows().contains(r).ok_or_else(index_error)
+    }
+
+    /// Vet the column index `c`.
+    #[inline]
+    pub fn vet_col(&self, c: usize) -> Result<Col<'c>, IndexingError> {
+        self.cols().contains(c).ok_or_else(index_error)
+    }
+
+    /// Vet the position at row `r` and column `c`.
+    #[inline]
+    pub fn vet(&self, r: usize, c: usize) -> Result<(Row<'r>, Col<'c>), IndexingError> {
+        Ok((self.vet_row(r)?, self.vet_col(c)?))
+    }
+
+    /// Return the row `r` as a slice.
+    #[inline]
+    pub fn row(&self, r: Row<'r>) -> &[T]
+        where Array: Contiguous
+    {
+        unsafe {
+            slice::from_raw_parts(self.arr.begin().add(r.index * self.ncols), self.ncols)
+        }
+    }
+
+    /// Return the row `r` as a mutable slice.
+    #[inline]
+    pub fn row_mut(&mut self, r: Row<'r>) -> &mut [T]
+        where Array: ContiguousMut
+    {
+        unsafe {
+            slice::from_raw_parts_mut(self.arr.begin_mut().add(r.index * self.ncols), self.ncols)
+        }
+    }
+
+    /// Return an iterator of the elements in column `c`, from the first row
+    /// to the last.
+    #[inline]
+    pub fn column(&self, c: Col<'c>) -> Column<'_, 'r, 'c, Array>
+        where Array: GetUnchecked
+    {
+        Column {
+            grid: self,
+            col: c,
+            rows: self.rows().into_iter(),
+        }
+    }
+
+    /// Return an iterator of the rows of the sub-rectangle `rows` by `cols`,
+    /// as slices.
+    #[inline]
+    pub fn rect<P, Q>(&self, rows: Range<'r, P>, cols: Range<'c, Q>) -> Rect<'_, 'r, T>
+        where Array: Contiguous
+    {
+        Rect {
+            // wrapping, since the grid may have no rows
+            ptr: self.arr.begin().wrapping_add(cols.start),
+            ncols: self.ncols,
+            width: cols.len(),
+            rows: rows.no_proof().into_iter(),
+            life: PhantomData,
+        }
+    }
+
+    /// Return an iterator of the rows of the sub-rectangle `rows` by `cols`,
+    /// as mutable slices.
+    #[inline]
+    pub fn rect_mut<P, Q>(&mut self, rows: Range<'r, P>, cols: Range<'c, Q>) -> RectMut<'_, 'r, T>
+        where Array: ContiguousMut
+    {
+        RectMut {
+            ptr: self.arr.begin_mut().wrapping_add(cols.start),
+            ncols: self.ncols,
+            width: cols.len(),
+            rows: rows.no_proof().into_iter(),
+            life: PhantomData,
+        }
+    }
+
+    /// Return an iterator of the positions directly above, left, right
+    /// and below of `(r, c)`, that are inside the grid.
+    #[inline]
+    pub fn neighbors4(&self, r: Row<'r>, c: Col<'c>) -> Neighbors<'r, 'c> {
+        self.neighbors(r, c, &OFFSETS4)
+    }
+
+    /// Return an iterator of the positions around `(r, c)`, including the
+    /// diagonals, that are inside the grid.
+    #[inline]
+    pub fn neighbors8(&self, r: Row<'r>, c: Col<'c>) -> Neighbors<'r, 'c> {
+        self.neighbors(r, c, &OFFSETS8)
+    }
+
+    fn neighbors(&self, r: Row<'r>, c: Col<'c>, offsets: &'static [(isize, isize)])
+        -> Neighbors<'r, 'c>
+    {
+        Neighbors {
+            row: r,
+            col: c,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            offsets,
+        }
+    }
+}
+
+/// `&self[(r, c)]` where `r` is a `Row<'r>` and `c` is a `Col<'c>`.
+impl<'r, 'c, Array> ops::Index<(Row<'r>, Col<'c>)> for Grid<'r, 'c, Array>
+    where Array: GetUnchecked
+{
+    type Output = Array::Item;
+    #[inline(always)]
+    fn index(&self, (r, c): (Row<'r>, Col<'c>)) -> &Self::Output {
+        unsafe {
+            self.arr.xget_unchecked(r.index * self.ncols + c.index)
+        }
+    }
+}
+
+/// `&mut self[(r, c)]` where `r` is a `Row<'r>` and `c` is a `Col<'c>`.
+impl<'r, 'c, Array> ops::IndexMut<(Row<'r>, Col<'c>)> for Grid<'r, 'c, Array>
+    where Array: GetUncheckedMut
+{
+    #[inline(always)]
+    fn index_mut(&mut self, (r, c): (Row<'r>, Col<'c>)) -> &mut Self::Output {
+        unsafe {
+            self.arr.xget_unchecked_mut(r.index * self.ncols + c.index)
+        }
+    }
+}
+
+/// An iterator of the elements in a column of a `Grid`.
+pub struct Column<'a, 'r, 'c, Array: 'a> {
+    grid: &'a Grid<'r, 'c, Array>,
+    col: Col<'c>,
+    rows: RangeIter<'r>,
+}
+
+impl<'a, 'r, 'c, Array> Iterator for Column<'a, 'r, 'c, Array>
+    where Array: GetUnchecked
+{
+    type Item = &'a Array::Item;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let grid = self.grid;
+        let col = self.col;
+        self.rows.next().map(move |r| &grid[(r, col)])
+    }
+}
+
+impl<'a, 'r, 'c, Array> DoubleEndedIterator for Column<'a, 'r, 'c, Array>
+    where Array: GetUnchecked
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let grid = self.grid;
+        let col = self.col;
+        self.rows.next_back().map(move |r| &grid[(r, col)])
+    }
+}
+
+/// An iterator of the rows of a sub-rectangle of a `Grid`.
+pub struct Rect<'a, 'r, T> {
+    // pointer to the first column of the rectangle, in row 0
+    ptr: *const T,
+    ncols: usize,
+    width: usize,
+    rows: RangeIter<'r>,
+    life: PhantomData<&'a [T]>,
+}
+
+impl<'a, 'r, T> Iterator for Rect<'a, 'r, T> {
+    type Item = &'a [T];
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(|r| unsafe {
+            slice::from_raw_parts(self.ptr.add(r.index * self.ncols), self.width)
+        })
+    }
+}
+
+/// An iterator of the rows of a sub-rectangle of a `Grid`, as mutable slices.
+pub struct RectMut<'a, 'r, T> {
+    // pointer to the first column of the rectangle, in row 0
+    ptr: *mut T,
+    ncols: usize,
+    width: usize,
+    rows: RangeIter<'r>,
+    life: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, 'r, T> Iterator for RectMut<'a, 'r, T> {
+    type Item = &'a mut [T];
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // each row is yielded once, and the rows do not overlap
+        self.rows.next().map(|r| unsafe {
+            slice::from_raw_parts_mut(self.ptr.add(r.index * self.ncols), self.width)
+        })
+    }
+}
+
+const OFFSETS4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const OFFSETS8: [(isize, isize); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1), (0, 1),
+    (1, -1), (1, 0), (1, 1),
+];
+
+/// An iterator of the neighboring positions of a position in a `Grid`.
+///
+/// Iterator element type is `(Row<'r>, Col<'c>)`; only positions inside the
+/// grid are produced.
+#[derive(Copy, Clone, Debug)]
+pub struct Neighbors<'r, 'c> {
+    row: Row<'r>,
+    col: Col<'c>,
+    nrows: usize,
+    ncols: usize,
+    offsets: &'static [(isize, isize)],
+}
+
+impl<'r, 'c> Iterator for Neighbors<'r, 'c> {
+    type Item = (Row<'r>, Col<'c>);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((&(dr, dc), rest)) = self.offsets.split_first() {
+            self.offsets = rest;
+            let r = self.row.index.checked_add_signed(dr).filter(|&r| r < self.nrows);
+            let c = self.col.index.checked_add_signed(dc).filter(|&c| c < self.ncols);
+            if let (Some(r), Some(c)) = (r, c) {
+                unsafe {
+                    return Some((Index::new(r), Index::new(c)));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn test_grid() {
+    let mut data = [0, 1, 2,
+                    3, 4, 5];
+    scope_grid(&mut data[..], 2, 3, |mut g| {
+        let (r, c) = g.vet(1, 2).unwrap();
+        assert_eq!(g[(r, c)], 5);
+        assert!(g.vet(2, 0).is_err());
+        assert_eq!(g.row(r), &[3, 4, 5]);
+        assert!(g.column(c).eq(&[2, 5]));
+
+        assert!(g.neighbors4(r, c).map(|(r, c)| g[(r, c)]).eq([2, 4]));
+        let (r0, c0) = g.vet(0, 1).unwrap();
+        assert!(g.neighbors8(r0, c0).map(|(r, c)| g[(r, c)]).eq([0, 2, 3, 4, 5]));
+
+        let (_, right, _) = g.cols().split_at(1);
+        for row in g.rect_mut(g.rows(), right) {
+            for elt in row {
+                *elt *= 10;
+            }
+        }
+        g[(r0, c0)] = -1;
+    }).unwrap();
+    assert_eq!(data, [0, -1, 20,
+                      3, 40, 50]);
+    assert!(scope_grid(&data[..], 4, 2, |_| ()).is_err());
+}
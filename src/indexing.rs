@@ -145,7 +145,9 @@ impl<'id, P> Range<'id, P> {
     /// Split the range in half, with the upper middle index landing in the
     /// latter half. Proof of length `P` transfers to the latter half.
     #[inline]
-    pub fn split_in_half(self) -> (Range<'id>, Range<'id, P>) {
+    pub fn split_in_half(self) -> (Range<'id>, Range<'id, P>)
+        where P: LengthMarker
+    {
         let mid = (self.end - self.start) / 2 + self.start;
         unsafe {
             (Range::from(self.start, mid), Range::from_any(mid, self.end))
@@ -217,7 +219,7 @@ impl<'id, P> Range<'id, P> {
     ///     let left = arr.vet_range(0..2).unwrap();
     ///     let left = left.nonempty().unwrap();
     ///     let (_, right) = arr.range().frontiers();
-
+    ///
     ///     let joined = right.join_cover(left);
     ///     let ix = joined.first();
     ///     arr[ix];  //~ ERROR: Can't index by ix, because it's an edge index
@@ -330,7 +332,9 @@ impl<'id, P> Range<'id, P> {
     ///
     /// Produces `mid` where `mid = start + len / 2`.
     #[inline]
-    pub fn upper_middle(&self) -> Index<'id, P> {
+    pub fn upper_middle(&self) -> Index<'id, P>
+        where P: LengthMarker
+    {
         let mid = self.len() / 2 + self.start;
         unsafe {
             Index::new(mid)
@@ -462,6 +466,7 @@ impl<'id, P> IntoIterator for Range<'id, P> {
 /// Iterator element type is `Index<'id>`.
 #[derive(Copy, Clone, Debug)]
 pub struct RangeIter<'id> {
+    #[allow(dead_code)]
     id: Id<'id>,
     start: usize,
     end: usize,
@@ -547,10 +552,10 @@ impl FracStep {
         }
         FracStep {
             f: Frac(start, 0, divisor),
-            frac_step: frac_step,
-            decimal_step: decimal_step,
-            start: start,
-            end: end,
+            frac_step,
+            decimal_step,
+            start,
+            end,
         }
     }
 
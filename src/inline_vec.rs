@@ -0,0 +1,197 @@
+//! A fixed capacity vector, that stores its elements inline.
+
+use std::fmt::{self, Debug};
+use std::mem::MaybeUninit;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::slice;
+
+use crate::container_traits::*;
+
+/// A vector with fixed capacity `CAP`, that stores its elements inline.
+///
+/// It needs no allocator, and it can be used as a `Pushable` container
+/// with `no_std`.
+pub struct InlineVec<T, const CAP: usize> {
+    len: usize,
+    data: [MaybeUninit<T>; CAP],
+}
+
+impl<T, const CAP: usize> InlineVec<T, CAP> {
+    /// Create a new empty vector.
+    pub fn new() -> Self {
+        InlineVec {
+            len: 0,
+            data: [const { MaybeUninit::uninit() }; CAP],
+        }
+    }
+
+    /// Return the capacity of the vector.
+    #[inline]
+    pub fn capacity(&self) -> usize { CAP }
+
+    /// Return `true` if the vector is full.
+    #[inline]
+    pub fn is_full(&self) -> bool { self.len == CAP }
+
+    /// Append `item` to the vector, or return it if the vector is full.
+    #[inline]
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        unsafe {
+            *self.data.get_unchecked_mut(self.len) = MaybeUninit::new(item);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Append `item` to the vector.
+    ///
+    /// ***Panics*** if the vector is full.
+    #[inline]
+    pub fn push(&mut self, item: T) {
+        if self.try_push(item).is_err() {
+            panic!("InlineVec::push: vector is full");
+        }
+    }
+
+    /// Insert `item` at `index`, shifting the elements after it.
+    ///
+    /// ***Panics*** if `index` is out of bounds or the vector is full.
+    pub fn insert(&mut self, index: usize, item: T) {
+        assert!(index <= self.len, "InlineVec::insert: index out of bounds");
+        assert!(!self.is_full(), "InlineVec::insert: vector is full");
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.add(1), self.len - index);
+            ptr::write(p, item);
+        }
+        self.len += 1;
+    }
+
+    /// Remove the last element and return it, or `None` if empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            Some(ptr::read(self.data.get_unchecked(self.len).as_ptr()))
+        }
+    }
+}
+
+impl<T, const CAP: usize> Default for InlineVec<T, CAP> {
+    fn default() -> Self {
+        InlineVec::new()
+    }
+}
+
+impl<T, const CAP: usize> Drop for InlineVec<T, CAP> {
+    fn drop(&mut self) {
+        let elements = &mut **self as *mut [T];
+        self.len = 0;
+        unsafe {
+            ptr::drop_in_place(elements);
+        }
+    }
+}
+
+impl<T, const CAP: usize> Deref for InlineVec<T, CAP> {
+    type Target = [T];
+    #[inline]
+    fn deref(&self) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(self.data.as_ptr() as *const T, self.len)
+        }
+    }
+}
+
+impl<T, const CAP: usize> DerefMut for InlineVec<T, CAP> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.len)
+        }
+    }
+}
+
+impl<T: Debug, const CAP: usize> Debug for InlineVec<T, CAP> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+unsafe impl<T, const CAP: usize> Trustworthy for InlineVec<T, CAP> {
+    type Item = T;
+    fn base_len(&self) -> usize { self.len }
+}
+
+unsafe impl<T, const CAP: usize> ContiguousMut for InlineVec<T, CAP> {
+    fn begin_mut(&mut self) -> *mut Self::Item { (**self).begin_mut() }
+    fn end_mut(&mut self) -> *mut Self::Item { (**self).end_mut() }
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+        self
+    }
+}
+
+unsafe impl<T, const CAP: usize> GetUnchecked for InlineVec<T, CAP> {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        self.get_unchecked(i)
+    }
+}
+
+unsafe impl<T, const CAP: usize> GetUncheckedMut for InlineVec<T, CAP> {
+    unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+        self.get_unchecked_mut(i)
+    }
+}
+
+unsafe impl<T, const CAP: usize> Contiguous for InlineVec<T, CAP> {
+    fn begin(&self) -> *const Self::Item {
+        (**self).begin()
+    }
+    fn end(&self) -> *const Self::Item {
+        (**self).end()
+    }
+    fn as_slice(&self) -> &[Self::Item] {
+        self
+    }
+}
+
+unsafe impl<T, const CAP: usize> Pushable for InlineVec<T, CAP> {
+    fn push(&mut self, item: T) -> usize {
+        let i = self.len;
+        self.push(item);
+        i
+    }
+    fn try_push(&mut self, item: T) -> Result<usize, T> {
+        let i = self.len;
+        self.try_push(item).map(|_| i)
+    }
+    unsafe fn insert_unchecked(&mut self, index: usize, item: Self::Item) {
+        self.insert(index, item)
+    }
+}
+
+#[test]
+fn test_inline_vec() {
+    use crate::scope;
+
+    let mut v = InlineVec::<i32, 4>::new();
+    v.push(1);
+    scope(&mut v, |v| {
+        let mut v = v.only_index();
+        let i = v.push(2);
+        let j = v.try_push(3).unwrap();
+        v.insert(i, 0);
+        assert_eq!(v[j], 2);
+        assert_eq!(v.try_push(4), Err(4));
+    });
+    assert_eq!(&v[..], &[1, 0, 2, 3]);
+    assert_eq!(v.pop(), Some(3));
+    assert_eq!(v.len(), 3);
+}
@@ -27,9 +27,9 @@
 //! - `Index<'id>` is a trusted index
 //! - `Range<'id, P>` is a trusted range.
 //! - For a range, if the proof parameter `P` is `NonEmpty`, then the range is
-//! known to have at least one element. An observation: A non-empty range always
-//! has a valid front index, so it is interchangeable with the index
-//! representation.
+//!   known to have at least one element. An observation: A non-empty range always
+//!   has a valid front index, so it is interchangeable with the index
+//!   representation.
 //! - indices and pointers also use the same proof parameter. A `NonEmpty`
 //!   index points to a valid element, while an `Unknown` index is an edge
 //!   index (it can be used to slice the container, but not to dereference to
@@ -46,16 +46,16 @@
 //! needs revision and it's not of good quality, so it's not enabled by default.
 //!
 //! - `PIndex<'id, T>` and `PRange<'id, T, P>` are equivalent to `Index` and
-//! `Range`, but they use trusted raw pointers instead.
-//! There are even two kinds of ranges: `PRange` uses a begin and end pointer
-//! representation, and `PSlice` a begin pointer and length representation.
+//!   `Range`, but they use trusted raw pointers instead.
+//!   There are even two kinds of ranges: `PRange` uses a begin and end pointer
+//!   representation, and `PSlice` a begin pointer and length representation.
 //!
 //! # Borrowing Rules
 //!
 //! - The indices, pointers and ranges are freely copyable and do not track
-//! mutability or exclusive access themselves. All access to the underlying data
-//! goes through the Container, for example by indexing the container with
-//! a trusted particle.
+//!   mutability or exclusive access themselves. All access to the underlying data
+//!   goes through the Container, for example by indexing the container with
+//!   a trusted particle.
 //!
 //!
 //!
@@ -102,6 +102,9 @@
 #[cfg(not(feature = "use_std"))]
 extern crate core as std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use std::marker::PhantomData;
 use std::fmt::{self, Debug};
 
@@ -111,6 +114,22 @@ pub mod proof;
 pub mod algorithms;
 pub mod container_traits;
 pub mod container;
+pub mod slice_backed;
+pub mod strided;
+pub mod grid;
+pub mod segments;
+pub mod uninit;
+pub mod inline_vec;
+pub mod reversed;
+pub mod bits;
+pub mod soa;
+pub mod branded;
+pub mod sub_scope;
+pub mod ghost_cell;
+#[cfg(feature = "alloc")]
+pub mod gap_buffer;
+#[cfg(feature = "alloc")]
+pub mod append_vec;
 #[cfg(feature="experimental_pointer_ranges")]
 pub mod pointer;
 mod index_error;
@@ -118,9 +137,17 @@ mod pointer_ext;
 
 pub use crate::index_error::IndexingError;
 
-pub use crate::container::{Container, scope};
+pub use crate::container::{Container, scope, scope_array, scope_cells, scope_slice, scope_slice_mut,
+                           scope_twins};
+#[cfg(feature = "async")]
+pub use crate::container::scope_async;
+
+pub use crate::proof::{NonEmpty, Unknown, CharBoundary, Init};
 
-pub use crate::proof::{NonEmpty, Unknown};
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::container::{Brand, BrandGuard, make_container};
+}
 
 
 // Common types //
@@ -142,6 +169,20 @@ impl<'id> Default for Id<'id> {
     }
 }
 
+/// Create the brand of a new container.
+///
+/// # Safety
+///
+/// The brand must only be handed to a closure that works for any `'id`
+/// (a `for<'id> FnOnce(..)` bound), together with values that are branded
+/// with it. The closure can't choose `'id`, and the caller's borrowck sees
+/// an opaque fresh lifetime that it can't unify with any other brand, so the
+/// brand is unique; see `scope` for the details.
+#[inline]
+pub(crate) unsafe fn fresh_brand<'id>() -> Id<'id> {
+    Id::default()
+}
+
 impl<'id> Debug for Id<'id> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str("Id<'id>")
@@ -158,7 +199,10 @@ impl<'id> Debug for Id<'id> {
 ///
 /// A `NonEmpty` index points to a valid element. An `Unknown` index is unknown,
 /// or it points to an edge index (just past the end).
+// repr(transparent), so that a slice of `RawIndex` can be vetted in place
+#[repr(transparent)]
 pub struct Index<'id, Proof = NonEmpty> {
+    #[allow(dead_code)]
     id: Id<'id>,
     index: usize,
     /// NonEmpty or Unknown
@@ -170,7 +214,7 @@ impl<'id, P> Index<'id, P> {
     #[inline(always)]
     unsafe fn new(index: usize) -> Index<'id, P> {
         debug_assert!(index as isize >= 0);
-        Index { id: Id::default(), index: index, proof: PhantomData }
+        Index { id: Id::default(), index, proof: PhantomData }
     }
 
     #[inline]
@@ -207,7 +251,7 @@ impl<'id> Range<'id> {
     #[inline(always)]
     unsafe fn from(start: usize, end: usize) -> Range<'id> {
         debug_assert!(start <= end);
-        Range { id: Id::default(), start: start, end: end, proof: PhantomData }
+        Range { id: Id::default(), start, end, proof: PhantomData }
     }
 }
 
@@ -215,7 +259,7 @@ impl<'id> Range<'id, NonEmpty> {
     #[inline(always)]
     unsafe fn from_ne(start: usize, end: usize) -> Range<'id, NonEmpty> {
         debug_assert!(start < end);
-        Range { id: Id::default(), start: start, end: end, proof: PhantomData }
+        Range { id: Id::default(), start, end, proof: PhantomData }
     }
 
     #[inline]
@@ -228,7 +272,7 @@ impl<'id, P> Range<'id, P> {
     #[inline(always)]
     unsafe fn from_any(start: usize, end: usize) -> Range<'id, P> {
         debug_assert!(start <= end);
-        Range { id: Id::default(), start: start, end: end, proof: PhantomData }
+        Range { id: Id::default(), start, end, proof: PhantomData }
     }
 
     #[inline]
@@ -237,7 +281,68 @@ impl<'id, P> Range<'id, P> {
     }
 }
 
+/// An unbranded index, that can be stored outside of an indexing scope.
+///
+/// Convert an `Index` into it using `From`, and vet it again against a
+/// container using `Container::revet_all`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct RawIndex(usize);
+
+impl RawIndex {
+    /// Create a raw index of `index`.
+    #[inline]
+    pub fn new(index: usize) -> Self {
+        RawIndex(index)
+    }
+
+    /// Return the index as an integer.
+    #[inline]
+    pub fn integer(self) -> usize {
+        self.0
+    }
+}
+
+impl<'id, P> From<Index<'id, P>> for RawIndex {
+    #[inline]
+    fn from(index: Index<'id, P>) -> Self {
+        RawIndex(index.index)
+    }
+}
+
+/// An unbranded range, that can be stored outside of an indexing scope.
+///
+/// Convert a `Range` into it using `From`, and vet it again against a
+/// container using `Container::revet_range`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RawRange {
+    start: usize,
+    end: usize,
+}
+
+impl RawRange {
+    /// Create a raw range of `start..end`.
+    #[inline]
+    pub fn new(r: std::ops::Range<usize>) -> Self {
+        RawRange { start: r.start, end: r.end }
+    }
+
+    /// Return the range as a range of integers.
+    #[inline]
+    pub fn integers(self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl<'id, P> From<Range<'id, P>> for RawRange {
+    #[inline]
+    fn from(r: Range<'id, P>) -> Self {
+        RawRange { start: r.start, end: r.end }
+    }
+}
+
 // Access the internals of Container in the whole crate (but not outside)
+#[allow(dead_code)]
 trait ContainerPrivate {
     type Array;
     fn array(&self) -> &Self::Array;
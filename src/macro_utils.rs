@@ -3,7 +3,7 @@
 ///
 /// Example
 /// 
-/// ```
+/// ```ignore
 /// struct Foo<T>(*const T);
 /// copy_and_clone!([T] Foo<T>);
 /// ```
@@ -8,6 +8,7 @@
 // except according to those terms.
 
 /// Extension methods for raw pointers
+#[cfg_attr(not(feature="experimental_pointer_ranges"), allow(dead_code))]
 pub trait PointerExt : Copy {
     unsafe fn offset(self, i: isize) -> Self;
 
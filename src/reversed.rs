@@ -0,0 +1,89 @@
+//! A reversed view of a container.
+
+use crate::container_traits::*;
+
+/// A view of the container `A` with its elements in reverse order.
+///
+/// Index `i` of the view is index `len - 1 - i` of the underlying container,
+/// so code that is written for a container, like a scan or a search, runs
+/// back to front when it is given a reversed view instead. For example,
+/// `lower_bound`, `binary_search_by`, `heapify` and `quicksort_indexed` in
+/// `algorithms` search or sort in descending order when given a view.
+///
+/// The view is not contiguous, so it can't be sliced, and methods that need
+/// a slice, like `rotate1_up`, are not available.
+///
+/// ```
+/// use indexing::scope;
+/// use indexing::reversed::Reversed;
+/// use indexing::algorithms::quicksort_indexed;
+///
+/// let data = [1, 2, 3, 4];
+/// scope(Reversed(&data[..]), |v| {
+///     let first = v.range().nonempty().unwrap().first();
+///     assert_eq!(v[first], 4);
+///     // scanning forward in the view scans the data back to front
+///     assert_eq!(v.scan_from(first, |&x| x > 2).len(), 2);
+/// });
+///
+/// let mut data = [3, 1, 4, 1, 5];
+/// quicksort_indexed(Reversed(&mut data[..]));
+/// assert_eq!(data, [5, 4, 3, 1, 1]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Reversed<A>(pub A);
+
+impl<A> Reversed<A> {
+    /// Return the underlying container.
+    pub fn into_inner(self) -> A {
+        self.0
+    }
+}
+
+unsafe impl<A> Trustworthy for Reversed<A>
+    where A: Trustworthy
+{
+    type Item = A::Item;
+    fn base_len(&self) -> usize {
+        self.0.base_len()
+    }
+}
+
+unsafe impl<A> GetUnchecked for Reversed<A>
+    where A: GetUnchecked
+{
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        self.0.xget_unchecked(self.0.base_len() - 1 - i)
+    }
+}
+
+unsafe impl<A> GetUncheckedMut for Reversed<A>
+    where A: GetUncheckedMut
+{
+    unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+        let j = self.0.base_len() - 1 - i;
+        self.0.xget_unchecked_mut(j)
+    }
+}
+
+unsafe impl<A> FixedLength for Reversed<A>
+    where A: FixedLength
+{ }
+
+#[test]
+fn test_reversed() {
+    use crate::scope;
+
+    let mut data = [1, 2, 3, 4, 5];
+    scope(Reversed(&mut data[..]), |mut v| {
+        let r = v.range().nonempty().unwrap();
+        assert_eq!(v[r.first()], 5);
+        assert_eq!(v[r.last()], 1);
+        let (a, b) = (r.first(), r.upper_middle());
+        v.swap(a, b);
+        let run = v.scan_from_rev(r.last(), |&x| x < 5);
+        assert_eq!(run.len(), 2);
+        v[r.last()] = 0;
+    });
+    assert_eq!(data, [0, 2, 5, 4, 3]);
+}
@@ -0,0 +1,401 @@
+//! Segmented (jagged) data: either in compressed form, a flat array of values
+//! and an array of offsets that delimit the segments (`scope_segments`), or
+//! as an array of arrays, like a `Vec<Vec<T>>` (`scope_nested`).
+//!
+//! In both forms, the segments are indexed with an `Index<'o>`, and each
+//! segment can be opened with a brand `'s` of its own, which proves that its
+//! indices and ranges belong to that segment.
+
+use std::ops;
+
+use crate::container::{Container, scope};
+use crate::container_traits::*;
+use crate::index_error::{IndexingError, index_error};
+use crate::sub_scope::SubSlice;
+use crate::{fresh_brand, Id, Index, Range};
+
+/// Branded segmented data.
+///
+/// Segment `i` is the range of values `offsets[i]..offsets[i + 1]`.
+/// Segment indices are branded with `'o` and the indices and ranges of the
+/// values with `'v`; the offsets are vetted once when the scope is created, so
+/// that looking up a segment's range needs no runtime bounds checking.
+///
+/// `&self[i]` where `i` is an `Index<'o>` is the slice of the segment's
+/// values.
+pub struct Segments<'o, 'v, Offsets, Values> {
+    #[allow(dead_code)]
+    id: Id<'o>,
+    offsets: Offsets,
+    values: Container<'v, Values>,
+}
+
+/// Create an indexing scope for segmented data.
+///
+/// `offsets` must be nonempty and nondecreasing, start with zero, and its
+/// last element must be equal to the length of `values`; otherwise an error
+/// is returned.
+pub fn scope_segments<Offsets, Values, F, Out>(offsets: Offsets, values: Values, f: F)
+    -> Result<Out, IndexingError>
+    where F: for<'o, 'v> FnOnce(Segments<'o, 'v, Offsets, Values>) -> Out,
+          Offsets: Contiguous<Item=usize> + FixedLength,
+          Values: Trustworthy + FixedLength,
+{
+    {
+        let o = offsets.as_slice();
+        match (o.first(), o.last()) {
+            (Some(&0), Some(&last)) if last == values.base_len() => { }
+            _ => return Err(index_error()),
+        }
+        if !o.windows(2).all(|w| w[0] <= w[1]) {
+            return Err(index_error());
+        }
+    }
+    Ok(scope(values, move |values| {
+        f(Segments { id: Id::default(), offsets, values })
+    }))
+}
+
+impl<'o, 'v, Offsets, Values> Segments<'o, 'v, Offsets, Values>
+    where Offsets: Contiguous<Item=usize>,
+          Values: Trustworthy,
+{
+    /// Return the number of segments.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets.base_len() - 1
+    }
+
+    /// Return `true` if there are no segments.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the range of all segments.
+    #[inline]
+    pub fn range(&self) -> Range<'o> {
+        unsafe {
+            Range::from(0, self.len())
+        }
+    }
+
+    /// Vet the segment index `index`.
+    #[inline]
+    pub fn vet(&self, index: usize) -> Result<Index<'o>, IndexingError> {
+        self.range().contains(index).ok_or_else(index_error)
+    }
+
+    /// Return the range of the values in segment `i`.
+    ///
+    /// The range has the brand of the values, so it only proves that it is
+    /// in bounds for the values; use `.segment()` for a range that proves
+    /// that it belongs to the segment.
+    #[inline]
+    pub fn segment_range(&self, i: Index<'o>) -> Range<'v> {
+        let o = self.offsets.as_slice();
+        unsafe {
+            Range::from(*o.get_unchecked(i.index), *o.get_unchecked(i.index + 1))
+        }
+    }
+
+    /// Pass the segment `i` to the closure, with a brand `'s` of its own.
+    ///
+    /// The indices and ranges of the segment are positions in the values,
+    /// and its brand proves that they are in segment `i`. Use `.lift()` to
+    /// index the values with them.
+    ///
+    /// ```
+    /// use indexing::segments::scope_segments;
+    ///
+    /// let offsets = [0, 2, 5];
+    /// let values = [1, 2, 3, 4, 5];
+    /// scope_segments(&offsets[..], &values[..], |segs| {
+    ///     let i = segs.vet(1).unwrap();
+    ///     segs.segment(i, |seg| {
+    ///         assert!(seg.vet(1).is_err());
+    ///         let last = seg.range().nonempty().unwrap().last();
+    ///         assert_eq!(last.integer(), 4);
+    ///         assert_eq!(segs.values()[seg.lift(last)], 5);
+    ///     });
+    /// }).unwrap();
+    /// ```
+    #[inline]
+    pub fn segment<F, Out>(&self, i: Index<'o>, f: F) -> Out
+        where F: for<'s> FnOnce(Segment<'s, 'v>) -> Out,
+    {
+        let range = self.segment_range(i);
+        f(Segment { id: unsafe { fresh_brand() }, range })
+    }
+
+    /// Open a sub-scope over the values of segment `i`.
+    ///
+    /// The closure is passed a container of the segment's values, with a
+    /// fresh brand `'s`, so its indices and ranges can't be used with other
+    /// segments. Use `.lift()` to convert them to indices of the values.
+    ///
+    /// ```
+    /// use indexing::segments::scope_segments;
+    ///
+    /// let offsets = [0, 2, 5];
+    /// let mut values = [1, 2, 3, 4, 5];
+    /// scope_segments(&offsets[..], &mut values[..], |mut segs| {
+    ///     let i = segs.vet(1).unwrap();
+    ///     segs.segment_scope(i, |mut seg| {
+    ///         let r = seg.range().nonempty().unwrap();
+    ///         seg.swap(r.first(), r.last());
+    ///     });
+    /// }).unwrap();
+    /// assert_eq!(values, [1, 2, 5, 4, 3]);
+    /// ```
+    pub fn segment_scope<'a, T, F, Out>(&'a mut self, i: Index<'o>, f: F) -> Out
+        where Values: ContiguousMut<Item=T>,
+              T: 'a,
+              F: for<'s> FnOnce(Container<'s, SubSlice<'v, 'a, T>>) -> Out,
+    {
+        let r = self.segment_range(i);
+        self.values.sub_scope(r, f)
+    }
+
+    /// Return the container of the values.
+    #[inline]
+    pub fn values(&self) -> &Container<'v, Values> {
+        &self.values
+    }
+
+    /// Return the container of the values.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut Container<'v, Values> {
+        &mut self.values
+    }
+}
+
+/// `&self[i]` where `i` is an `Index<'o>`: the values of segment `i`.
+impl<'o, 'v, Offsets, Values, T> ops::Index<Index<'o>> for Segments<'o, 'v, Offsets, Values>
+    where Offsets: Contiguous<Item=usize>,
+          Values: Contiguous<Item=T>,
+{
+    type Output = [T];
+    #[inline(always)]
+    fn index(&self, i: Index<'o>) -> &[T] {
+        &self.values[self.segment_range(i)]
+    }
+}
+
+/// `&mut self[i]` where `i` is an `Index<'o>`: the values of segment `i`.
+impl<'o, 'v, Offsets, Values, T> ops::IndexMut<Index<'o>> for Segments<'o, 'v, Offsets, Values>
+    where Offsets: Contiguous<Item=usize>,
+          Values: ContiguousMut<Item=T>,
+{
+    #[inline(always)]
+    fn index_mut(&mut self, i: Index<'o>) -> &mut [T] {
+        let r = self.segment_range(i);
+        &mut self.values[r]
+    }
+}
+
+/// One segment of `Segments`, with the brand `'s`.
+///
+/// Its indices and ranges are positions in the values, like those of the
+/// values' brand `'v`, and they convert to them with `.lift()`.
+#[derive(Copy, Clone, Debug)]
+pub struct Segment<'s, 'v> {
+    #[allow(dead_code)]
+    id: Id<'s>,
+    range: Range<'v>,
+}
+
+impl<'s, 'v> Segment<'s, 'v> {
+    /// Return the range of the segment.
+    #[inline]
+    pub fn range(&self) -> Range<'s> {
+        unsafe {
+            Range::from(self.range.start, self.range.end)
+        }
+    }
+
+    /// Vet the absolute position `index` of the values, which must be in
+    /// the segment.
+    #[inline]
+    pub fn vet(&self, index: usize) -> Result<Index<'s>, IndexingError> {
+        self.range().contains(index).ok_or_else(index_error)
+    }
+
+    /// Convert the index `i` of the segment to an index of the values.
+    #[inline]
+    pub fn lift<P>(&self, i: Index<'s, P>) -> Index<'v, P> {
+        unsafe {
+            Index::new(i.index)
+        }
+    }
+
+    /// Convert the range `r` of the segment to a range of the values.
+    #[inline]
+    pub fn lift_range<P>(&self, r: Range<'s, P>) -> Range<'v, P> {
+        unsafe {
+            Range::from_any(r.start, r.end)
+        }
+    }
+
+    /// Convert the index `i` of the values to an index of the segment, if
+    /// it is in the segment.
+    #[inline]
+    pub fn lower(&self, i: Index<'v>) -> Option<Index<'s>> {
+        self.range().contains(i.index)
+    }
+}
+
+/// Branded segmented data, stored as an array of arrays.
+///
+/// Segment indices are branded with `'o`, and each segment is opened as a
+/// container with a brand of its own by `.segment()` or `.segment_mut()`.
+///
+/// `&self[i]` where `i` is an `Index<'o>` is the segment.
+pub struct Nested<'o, Array> {
+    segments: Container<'o, Array>,
+}
+
+/// Create an indexing scope for segmented data stored as an array of
+/// arrays, like a `Vec<Vec<T>>`.
+///
+/// ```
+/// use indexing::segments::scope_nested;
+///
+/// let mut rows = [[1, 2, 3], [4, 5, 6]];
+/// scope_nested(&mut rows[..], |mut rows| {
+///     let i = rows.vet(1).unwrap();
+///     rows.segment_mut(i, |mut row| {
+///         let r = row.range().nonempty().unwrap();
+///         row.swap(r.first(), r.last());
+///     });
+///     assert_eq!(rows[i], [6, 5, 4]);
+/// });
+/// ```
+pub fn scope_nested<Array, F, Out>(segments: Array, f: F) -> Out
+    where F: for<'o> FnOnce(Nested<'o, Array>) -> Out,
+          Array: Trustworthy,
+{
+    scope(segments, move |segments| f(Nested { segments }))
+}
+
+impl<'o, Array> Nested<'o, Array>
+    where Array: GetUnchecked,
+          Array::Item: Trustworthy,
+{
+    /// Return the number of segments.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Return `true` if there are no segments.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Return the range of all segments.
+    #[inline]
+    pub fn range(&self) -> Range<'o> {
+        self.segments.range()
+    }
+
+    /// Vet the segment index `index`.
+    #[inline]
+    pub fn vet(&self, index: usize) -> Result<Index<'o>, IndexingError> {
+        self.segments.vet(index)
+    }
+
+    /// Pass segment `i` to the closure, as a container with a brand `'s` of
+    /// its own.
+    pub fn segment<'a, F, Out>(&'a self, i: Index<'o>, f: F) -> Out
+        where F: for<'s> FnOnce(Container<'s, &'a Array::Item>) -> Out,
+    {
+        scope(&self.segments[i], f)
+    }
+
+    /// Pass segment `i` to the closure, as a mutable container with a brand
+    /// `'s` of its own.
+    pub fn segment_mut<'a, F, Out>(&'a mut self, i: Index<'o>, f: F) -> Out
+        where F: for<'s> FnOnce(Container<'s, &'a mut Array::Item>) -> Out,
+              Array: GetUncheckedMut,
+    {
+        scope(&mut self.segments[i], f)
+    }
+}
+
+/// `&self[i]` where `i` is an `Index<'o>`: segment `i`.
+impl<'o, Array> ops::Index<Index<'o>> for Nested<'o, Array>
+    where Array: GetUnchecked,
+{
+    type Output = Array::Item;
+    #[inline(always)]
+    fn index(&self, i: Index<'o>) -> &Array::Item {
+        &self.segments[i]
+    }
+}
+
+/// `&mut self[i]` where `i` is an `Index<'o>`: segment `i`.
+impl<'o, Array> ops::IndexMut<Index<'o>> for Nested<'o, Array>
+    where Array: GetUncheckedMut,
+{
+    #[inline(always)]
+    fn index_mut(&mut self, i: Index<'o>) -> &mut Array::Item {
+        &mut self.segments[i]
+    }
+}
+
+#[test]
+fn test_segments() {
+    let offsets = [0, 2, 2, 5];
+    let mut values = [1, 2, 3, 4, 5];
+    scope_segments(&offsets[..], &mut values[..], |mut segs| {
+        assert_eq!(segs.len(), 3);
+        let i = segs.vet(2).unwrap();
+        assert_eq!(&segs[i], &[3, 4, 5]);
+        assert!(segs.vet(3).is_err());
+        let empty = segs.vet(1).unwrap();
+        assert!(segs.segment_range(empty).is_empty());
+
+        let r = segs.segment_range(i).nonempty().unwrap();
+        assert_eq!(segs.values()[r.last()], 5);
+        for elt in &mut segs[i] {
+            *elt *= 10;
+        }
+        let j = segs.vet(0).unwrap();
+        let last = segs.segment_scope(j, |seg| {
+            assert_eq!(seg.len(), 2);
+            seg.lift(seg.vet(1).unwrap())
+        });
+        assert_eq!(segs.values()[last], 2);
+
+        let first = segs.values().vet(0).unwrap();
+        let (lowered, lifted) = segs.segment(i, |seg| {
+            assert_eq!(seg.range().start(), 2);
+            assert!(seg.vet(1).is_err());
+            let k = seg.vet(3).unwrap();
+            (seg.lower(first).is_some(), seg.lift(k))
+        });
+        assert!(!lowered);
+        assert_eq!(segs.values()[lifted], 40);
+    }).unwrap();
+    assert_eq!(values, [1, 2, 30, 40, 50]);
+
+    assert!(scope_segments(&[0, 3, 2][..], &values[..], |_| ()).is_err());
+    assert!(scope_segments(&[0, 2, 4][..], &values[..], |_| ()).is_err());
+    assert!(scope_segments(&[][..], &[0; 0][..], |_| ()).is_err());
+    assert!(scope_segments(&[1, 5][..], &values[..], |_| ()).is_err());
+}
+
+#[test]
+fn test_nested() {
+    let rows = [[1, 2], [3, 4], [5, 6]];
+    let sum = scope_nested(&rows[..], |rows| {
+        assert_eq!(rows.len(), 3);
+        assert!(rows.vet(3).is_err());
+        let i = rows.vet(1).unwrap();
+        assert_eq!(rows[i], [3, 4]);
+        rows.segment(i, |row| row.range().into_iter().map(|j| row[j]).sum::<i32>())
+    });
+    assert_eq!(sum, 7);
+}
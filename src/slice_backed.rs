@@ -0,0 +1,146 @@
+//! Containers for collections that implement the safe `SliceBacked` traits.
+
+use std::marker::PhantomData;
+
+use crate::container_traits::*;
+
+/// A container for a `SliceBacked` collection `S`.
+///
+/// The collection's slice is taken once, when the container is created, and
+/// the container borrows the collection for `'a`, so the length can't
+/// change while the container is in use, whatever `as_slice` returns later.
+///
+/// This is the array of the container in `scope_slice`.
+pub struct Sliced<'a, S: ?Sized + SliceBacked> {
+    slice: &'a [S::Item],
+    collection: PhantomData<&'a S>,
+}
+
+/// A container for a `SliceBackedMut` collection `S`.
+///
+/// Like `Sliced`, it takes the collection's mutable slice once. This is the
+/// array of the container in `scope_slice_mut`.
+pub struct SlicedMut<'a, S: ?Sized + SliceBackedMut> {
+    slice: &'a mut [S::Item],
+    collection: PhantomData<&'a mut S>,
+}
+
+impl<'a, S: ?Sized + SliceBacked> Sliced<'a, S> {
+    /// Take the slice of `collection`.
+    pub fn new(collection: &'a S) -> Self {
+        Sliced { slice: collection.as_slice(), collection: PhantomData }
+    }
+
+    /// Return the slice.
+    pub fn into_slice(self) -> &'a [S::Item] {
+        self.slice
+    }
+}
+
+impl<'a, S: ?Sized + SliceBackedMut> SlicedMut<'a, S> {
+    /// Take the mutable slice of `collection`.
+    pub fn new(collection: &'a mut S) -> Self {
+        SlicedMut { slice: collection.as_mut_slice(), collection: PhantomData }
+    }
+
+    /// Return the slice.
+    pub fn into_slice(self) -> &'a mut [S::Item] {
+        self.slice
+    }
+}
+
+impl<'a, S: ?Sized + SliceBacked> Copy for Sliced<'a, S> { }
+impl<'a, S: ?Sized + SliceBacked> Clone for Sliced<'a, S> {
+    fn clone(&self) -> Self { *self }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBacked> Trustworthy for Sliced<'a, S> {
+    type Item = S::Item;
+    fn base_len(&self) -> usize { self.slice.len() }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBacked> Contiguous for Sliced<'a, S> {
+    fn begin(&self) -> *const Self::Item { self.slice.begin() }
+    fn end(&self) -> *const Self::Item { self.slice.end() }
+    fn as_slice(&self) -> &[Self::Item] { self.slice }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBacked> GetUnchecked for Sliced<'a, S> {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        self.slice.get_unchecked(i)
+    }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBacked> FixedLength for Sliced<'a, S> { }
+
+unsafe impl<'a, S: ?Sized + SliceBackedMut> Trustworthy for SlicedMut<'a, S> {
+    type Item = S::Item;
+    fn base_len(&self) -> usize { self.slice.len() }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBackedMut> Contiguous for SlicedMut<'a, S> {
+    fn begin(&self) -> *const Self::Item { self.slice.begin() }
+    fn end(&self) -> *const Self::Item { self.slice.end() }
+    fn as_slice(&self) -> &[Self::Item] { self.slice }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBackedMut> ContiguousMut for SlicedMut<'a, S> {
+    fn begin_mut(&mut self) -> *mut Self::Item { self.slice.begin_mut() }
+    fn end_mut(&mut self) -> *mut Self::Item { self.slice.end_mut() }
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] { self.slice }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBackedMut> GetUnchecked for SlicedMut<'a, S> {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        self.slice.get_unchecked(i)
+    }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBackedMut> GetUncheckedMut for SlicedMut<'a, S> {
+    unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+        self.slice.get_unchecked_mut(i)
+    }
+}
+
+unsafe impl<'a, S: ?Sized + SliceBackedMut> FixedLength for SlicedMut<'a, S> { }
+
+/// Implement `SliceBacked` for a newtype, using the parameters (and bounds)
+/// as specified in `[]`, and the field that holds the elements.
+///
+/// The field can be anything that can be sliced with `[..]`, like a `Vec<T>`
+/// or a `Box<[T]>`. With `mut` before the field, `SliceBackedMut` is
+/// implemented too.
+///
+/// ```
+/// use indexing::{scope_slice_mut, slice_backed};
+///
+/// struct Triple { items: [i32; 3] }
+///
+/// slice_backed!(Triple, mut items: [i32]);
+///
+/// let mut triple = Triple { items: [1, 2, 3] };
+/// scope_slice_mut(&mut triple, |mut v| {
+///     let r = v.range().nonempty().unwrap();
+///     v.swap(r.first(), r.last());
+/// });
+/// assert_eq!(triple.items, [3, 2, 1]);
+/// ```
+#[macro_export]
+macro_rules! slice_backed {
+    ([$($parm:tt)*] $type_:ty, mut $field:tt : [$item:ty]) => {
+        $crate::slice_backed!{ [$($parm)*] $type_, $field: [$item] }
+
+        impl<$($parm)*> $crate::container_traits::SliceBackedMut for $type_ {
+            fn as_mut_slice(&mut self) -> &mut [$item] { &mut self.$field[..] }
+        }
+    };
+    ([$($parm:tt)*] $type_:ty, $field:tt : [$item:ty]) => {
+        impl<$($parm)*> $crate::container_traits::SliceBacked for $type_ {
+            type Item = $item;
+            fn as_slice(&self) -> &[$item] { &self.$field[..] }
+        }
+    };
+    ($type_:ty, $($rest:tt)*) => {
+        $crate::slice_backed!{ [] $type_, $($rest)* }
+    };
+}
@@ -0,0 +1,222 @@
+//! A struct-of-arrays container: a tuple of columns that share one brand and
+//! grow together.
+
+use crate::container::common_len;
+use crate::container_traits::*;
+use crate::index_error::{IndexingError, index_error};
+use crate::{Id, Index, Range};
+
+/// A tuple of columns that can be used in a `Soa`.
+///
+/// It is implemented for tuples of up to twelve columns, where every column
+/// is `Pushable` and `GetUncheckedMut`, like `(Vec<A>, Vec<B>, Vec<C>)`.
+///
+/// # Safety
+///
+/// If `base_len` returns `Some(len)`, every column must have at least `len`
+/// elements; `push_row` must append one element to every column, and
+/// `get_unchecked(_mut)` must return the elements at `i` of all the columns
+/// for every `i` that is in bounds for all of them.
+pub unsafe trait Columns {
+    /// A row of the table, a tuple of one element of each column.
+    type Row;
+    /// A tuple of references to the elements of a row.
+    type Refs<'a> where Self: 'a;
+    /// A tuple of mutable references to the elements of a row.
+    type Muts<'a> where Self: 'a;
+
+    /// Return the length of each column, if they are all equal.
+    fn base_len(&self) -> Option<usize>;
+
+    /// Append `row` to the columns.
+    fn push_row(&mut self, row: Self::Row);
+
+    /// # Safety
+    ///
+    /// `i` must be in bounds for all columns.
+    unsafe fn get_unchecked(&self, i: usize) -> Self::Refs<'_>;
+
+    /// # Safety
+    ///
+    /// `i` must be in bounds for all columns.
+    unsafe fn get_unchecked_mut(&mut self, i: usize) -> Self::Muts<'_>;
+}
+
+macro_rules! columns_impl {
+    ($($C:ident $c:ident $i:tt),+) => {
+        unsafe impl<$($C),+> Columns for ($($C,)+)
+            where $($C: Pushable + GetUncheckedMut),+
+        {
+            type Row = ($($C::Item,)+);
+            type Refs<'a> = ($(&'a $C::Item,)+) where Self: 'a;
+            type Muts<'a> = ($(&'a mut $C::Item,)+) where Self: 'a;
+
+            fn base_len(&self) -> Option<usize> {
+                common_len(&[$(self.$i.base_len()),+])
+            }
+
+            fn push_row(&mut self, row: Self::Row) {
+                let ($($c,)+) = row;
+                $(Pushable::push(&mut self.$i, $c);)+
+            }
+
+            unsafe fn get_unchecked(&self, i: usize) -> Self::Refs<'_> {
+                ($(self.$i.xget_unchecked(i),)+)
+            }
+
+            unsafe fn get_unchecked_mut(&mut self, i: usize) -> Self::Muts<'_> {
+                ($(self.$i.xget_unchecked_mut(i),)+)
+            }
+        }
+    }
+}
+
+columns_impl!(A a 0);
+columns_impl!(A a 0, B b 1);
+columns_impl!(A a 0, B b 1, C c 2);
+columns_impl!(A a 0, B b 1, C c 2, D d 3);
+columns_impl!(A a 0, B b 1, C c 2, D d 3, E e 4);
+columns_impl!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5);
+columns_impl!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6);
+columns_impl!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7);
+columns_impl!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8);
+columns_impl!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9);
+columns_impl!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9,
+              K k 10);
+columns_impl!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9,
+              K k 10, L l 11);
+
+/// A branded struct-of-arrays container.
+///
+/// All the columns share one brand, and `.push()` appends a row to every
+/// column at once, so an index is valid for every column. Rows are accessed
+/// with `.get(i)` and `.get_mut(i)`, which return a tuple of references,
+/// without runtime bounds checking.
+///
+/// ```
+/// use indexing::soa::scope_soa;
+/// use indexing::inline_vec::InlineVec;
+///
+/// let mut names = InlineVec::<&str, 8>::new();
+/// let mut ages = InlineVec::<u32, 8>::new();
+/// scope_soa((&mut names, &mut ages), |mut people| {
+///     let alice = people.push(("Alice", 30));
+///     let bob = people.push(("Bob", 25));
+///     *people.get_mut(bob).1 += 1;
+///     assert_eq!(people.get(alice), (&"Alice", &30));
+///     assert_eq!(people.get(bob), (&"Bob", &26));
+/// }).unwrap();
+/// assert_eq!(&names[..], ["Alice", "Bob"]);
+/// ```
+pub struct Soa<'id, C> {
+    #[allow(dead_code)]
+    id: Id<'id>,
+    columns: C,
+    // number of complete rows; the columns may be longer if a push panicked
+    len: usize,
+}
+
+/// Create an indexing scope for a struct-of-arrays container.
+///
+/// Return an error if the columns don't all have the same length.
+pub fn scope_soa<C, F, Out>(columns: C, f: F) -> Result<Out, IndexingError>
+    where F: for<'id> FnOnce(Soa<'id, C>) -> Out,
+          C: Columns,
+{
+    let len = columns.base_len().ok_or_else(index_error)?;
+    Ok(f(Soa { id: Id::default(), columns, len }))
+}
+
+impl<'id, C> Soa<'id, C>
+    where C: Columns,
+{
+    /// Return the number of rows.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no rows.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the range of all rows.
+    #[inline]
+    pub fn range(&self) -> Range<'id> {
+        unsafe {
+            Range::from(0, self.len)
+        }
+    }
+
+    /// Vet the row index `index`.
+    #[inline]
+    pub fn vet(&self, index: usize) -> Result<Index<'id>, IndexingError> {
+        self.range().contains(index).ok_or_else(index_error)
+    }
+
+    /// Append `row` to the columns, and return its index.
+    ///
+    /// All outstanding indices remain valid.
+    pub fn push(&mut self, row: C::Row) -> Index<'id> {
+        self.columns.push_row(row);
+        let i = self.len;
+        self.len += 1;
+        unsafe {
+            Index::new(i)
+        }
+    }
+
+    /// Return references to the elements of row `index`.
+    #[inline]
+    pub fn get(&self, index: Index<'id>) -> C::Refs<'_> {
+        unsafe {
+            self.columns.get_unchecked(index.index)
+        }
+    }
+
+    /// Return mutable references to the elements of row `index`.
+    #[inline]
+    pub fn get_mut(&mut self, index: Index<'id>) -> C::Muts<'_> {
+        unsafe {
+            self.columns.get_unchecked_mut(index.index)
+        }
+    }
+
+    /// Return the columns.
+    #[inline]
+    pub fn columns(&self) -> &C {
+        &self.columns
+    }
+
+    /// Return the columns.
+    pub fn into_inner(self) -> C {
+        self.columns
+    }
+}
+
+#[test]
+fn test_soa() {
+    use crate::inline_vec::InlineVec;
+
+    let mut xs = InlineVec::<i32, 4>::new();
+    let mut ys = InlineVec::<char, 4>::new();
+    xs.push(1);
+    ys.push('a');
+    scope_soa((&mut xs, &mut ys), |mut t| {
+        let i = t.vet(0).unwrap();
+        let j = t.push((2, 'b'));
+        assert_eq!(t.len(), 2);
+        let (x, y) = t.get_mut(i);
+        *x += 10;
+        *y = 'A';
+        assert_eq!(t.get(i), (&11, &'A'));
+        assert_eq!(t.get(j), (&2, &'b'));
+        assert!(t.vet(2).is_err());
+    }).unwrap();
+    assert_eq!(&xs[..], &[11, 2]);
+
+    ys.push('c');
+    assert!(scope_soa((&mut xs, &mut ys), |_| ()).is_err());
+}
@@ -0,0 +1,150 @@
+//! Strided views: containers of every `stride`-th element of a slice.
+//!
+//! A column of a row-major matrix or one channel of an interleaved buffer is
+//! a strided view.
+
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+use crate::container_traits::*;
+use crate::pointer_ext::PointerExt;
+
+/// A shared strided view of a slice.
+///
+/// Its elements are the slice's elements at `0, stride, 2 * stride, ...`.
+pub struct Strided<'a, T> {
+    ptr: *const T,
+    len: usize,
+    stride: isize,
+    life: PhantomData<&'a [T]>,
+}
+
+/// An exclusive strided view of a slice.
+///
+/// Its elements are the slice's elements at `0, stride, 2 * stride, ...`.
+pub struct StridedMut<'a, T> {
+    ptr: *mut T,
+    len: usize,
+    stride: isize,
+    life: PhantomData<&'a mut [T]>,
+}
+
+copy_and_clone!(['a, T] Strided<'a, T>);
+
+unsafe impl<'a, T: Sync> Send for Strided<'a, T> { }
+unsafe impl<'a, T: Sync> Sync for Strided<'a, T> { }
+unsafe impl<'a, T: Send> Send for StridedMut<'a, T> { }
+unsafe impl<'a, T: Sync> Sync for StridedMut<'a, T> { }
+
+/// Return the number of elements in a strided view of `len` elements.
+#[inline]
+fn strided_len(len: usize, stride: usize) -> usize {
+    assert!(stride != 0, "Strided: stride must be nonzero");
+    if len == 0 { 0 } else { (len - 1) / stride + 1 }
+}
+
+impl<'a, T> Strided<'a, T> {
+    /// Create a view of every `stride`-th element of `data`, starting
+    /// with the first.
+    ///
+    /// ***Panics*** if `stride` is zero.
+    pub fn new(data: &'a [T], stride: usize) -> Self {
+        Strided {
+            ptr: data.as_ptr(),
+            len: strided_len(data.len(), stride),
+            stride: stride as isize,
+            life: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> StridedMut<'a, T> {
+    /// Create a view of every `stride`-th element of `data`, starting
+    /// with the first.
+    ///
+    /// ***Panics*** if `stride` is zero.
+    pub fn new(data: &'a mut [T], stride: usize) -> Self {
+        StridedMut {
+            ptr: data.as_mut_ptr(),
+            len: strided_len(data.len(), stride),
+            stride: stride as isize,
+            life: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Debug> Debug for Strided<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+         .entries((0..self.len).map(|i| unsafe { self.xget_unchecked(i) }))
+         .finish()
+    }
+}
+
+impl<'a, T: Debug> Debug for StridedMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+         .entries((0..self.len).map(|i| unsafe { self.xget_unchecked(i) }))
+         .finish()
+    }
+}
+
+unsafe impl<'a, T> Trustworthy for Strided<'a, T> {
+    type Item = T;
+    fn base_len(&self) -> usize { self.len }
+}
+
+unsafe impl<'a, T> GetUnchecked for Strided<'a, T> {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        &*self.ptr.stride_offset(self.stride, i)
+    }
+}
+
+unsafe impl<'a, T> FixedLength for Strided<'a, T> { }
+
+unsafe impl<'a, T> Trustworthy for StridedMut<'a, T> {
+    type Item = T;
+    fn base_len(&self) -> usize { self.len }
+}
+
+unsafe impl<'a, T> GetUnchecked for StridedMut<'a, T> {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        &*self.ptr.stride_offset(self.stride, i)
+    }
+}
+
+// The stride is nonzero, so distinct indices give distinct elements.
+unsafe impl<'a, T> GetUncheckedMut for StridedMut<'a, T> {
+    unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+        &mut *self.ptr.stride_offset(self.stride, i)
+    }
+}
+
+unsafe impl<'a, T> FixedLength for StridedMut<'a, T> { }
+
+#[test]
+fn test_strided() {
+    use crate::scope;
+
+    // a 3 x 4 matrix in row major order
+    let mut data = [0, 1, 2, 3,
+                    4, 5, 6, 7,
+                    8, 9, 10, 11];
+    scope(Strided::new(&data[1..], 4), |col| {
+        assert_eq!(col.len(), 3);
+        let sum: i32 = col.range().into_iter().map(|i| col[i]).sum();
+        assert_eq!(sum, 1 + 5 + 9);
+    });
+    scope(StridedMut::new(&mut data[..], 2), |mut evens| {
+        assert_eq!(evens.len(), 6);
+        for i in evens.range() {
+            evens[i] *= 10;
+        }
+        let r = evens.range().nonempty().unwrap();
+        evens.swap(r.first(), r.last());
+    });
+    assert_eq!(data, [100, 1, 20, 3,
+                      40, 5, 60, 7,
+                      80, 9, 0, 11]);
+    scope(Strided::new(&data[..0], 3), |v| assert!(v.is_empty()));
+}
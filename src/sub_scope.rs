@@ -0,0 +1,144 @@
+//! Sub-scopes: a subrange of a container, rebranded as its own container.
+
+use crate::container::Container;
+use crate::container_traits::*;
+use crate::{fresh_brand, ContainerPrivate, Id, Index, Range};
+
+/// The array of a sub-scope: a mutable subslice of a container branded
+/// `'id`, that remembers where it starts in it.
+///
+/// See `Container::sub_scope`.
+pub struct SubSlice<'id, 'a, T> {
+    #[allow(dead_code)]
+    id: Id<'id>,
+    data: &'a mut [T],
+    start: usize,
+}
+
+impl<'id, 'a, Array, T, Mode> Container<'id, Array, Mode>
+    where Array: ContiguousMut<Item=T>,
+          T: 'a,
+{
+    /// Open a sub-scope over `&mut self[r]`.
+    ///
+    /// The subrange is passed to the closure as a container with a fresh
+    /// brand `'sub`. Its indices convert to and from indices of `self` with
+    /// `.lift()` and `.lower()`.
+    ///
+    /// ```
+    /// use indexing::scope;
+    ///
+    /// let mut data = [5, 1, 4, 2, 3];
+    /// scope(&mut data[..], |mut v| {
+    ///     let (_, tail, _) = v.range().split_at(2);
+    ///     let max = v.sub_scope(tail, |mut sub| {
+    ///         let r = sub.range().nonempty().unwrap();
+    ///         sub.swap(r.first(), r.last());
+    ///         sub.lift(r.first())
+    ///     });
+    ///     assert_eq!(v[max], 3);
+    /// });
+    /// assert_eq!(data, [5, 1, 3, 2, 4]);
+    /// ```
+    pub fn sub_scope<P, F, Out>(&'a mut self, r: Range<'id, P>, f: F) -> Out
+        where F: for<'sub> FnOnce(Container<'sub, SubSlice<'id, 'a, T>>) -> Out,
+    {
+        let start = r.start;
+        let data = &mut self[r];
+        let sub = SubSlice { id: Id::default(), data, start };
+        f(unsafe { Container::new_unchecked(fresh_brand(), sub) })
+    }
+}
+
+impl<'sub, 'id, 'a, T, Mode> Container<'sub, SubSlice<'id, 'a, T>, Mode> {
+    /// Convert the index `i` of the sub-scope to an index of the parent
+    /// container.
+    #[inline]
+    pub fn lift<P>(&self, i: Index<'sub, P>) -> Index<'id, P> {
+        unsafe {
+            Index::new(self.array().start + i.index)
+        }
+    }
+
+    /// Convert the range `r` of the sub-scope to a range of the parent
+    /// container.
+    #[inline]
+    pub fn lift_range<P>(&self, r: Range<'sub, P>) -> Range<'id, P> {
+        let start = self.array().start;
+        unsafe {
+            Range::from_any(start + r.start, start + r.end)
+        }
+    }
+
+    /// Convert the index `i` of the parent container to an index of the
+    /// sub-scope, if it is inside the sub-scope's range.
+    #[inline]
+    pub fn lower(&self, i: Index<'id>) -> Option<Index<'sub>> {
+        let sub = self.array();
+        let j = i.index.wrapping_sub(sub.start);
+        if j < sub.data.len() {
+            unsafe {
+                Some(Index::new(j))
+            }
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<'id, 'a, T> Trustworthy for SubSlice<'id, 'a, T> {
+    type Item = T;
+    fn base_len(&self) -> usize { self.data.len() }
+}
+
+unsafe impl<'id, 'a, T> Contiguous for SubSlice<'id, 'a, T> {
+    fn begin(&self) -> *const Self::Item { self.data.begin() }
+    fn end(&self) -> *const Self::Item { self.data.end() }
+    fn as_slice(&self) -> &[Self::Item] { self.data }
+}
+
+unsafe impl<'id, 'a, T> ContiguousMut for SubSlice<'id, 'a, T> {
+    fn begin_mut(&mut self) -> *mut Self::Item { self.data.begin_mut() }
+    fn end_mut(&mut self) -> *mut Self::Item { self.data.end_mut() }
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] { self.data }
+}
+
+unsafe impl<'id, 'a, T> GetUnchecked for SubSlice<'id, 'a, T> {
+    unsafe fn xget_unchecked(&self, i: usize) -> &Self::Item {
+        self.data.get_unchecked(i)
+    }
+}
+
+unsafe impl<'id, 'a, T> GetUncheckedMut for SubSlice<'id, 'a, T> {
+    unsafe fn xget_unchecked_mut(&mut self, i: usize) -> &mut Self::Item {
+        self.data.get_unchecked_mut(i)
+    }
+}
+
+unsafe impl<'id, 'a, T> FixedLength for SubSlice<'id, 'a, T> { }
+
+#[test]
+fn test_sub_scope() {
+    use crate::scope;
+
+    let mut data = [0, 1, 2, 3, 4, 5];
+    scope(&mut data[..], |mut v| {
+        let (_, mid, _) = v.range().split_at(2);
+        let (mid, _, _) = mid.split_at(3);
+        let outside = v.vet(5).unwrap();
+        let inside = v.vet(3).unwrap();
+        let lifted = v.sub_scope(mid, |mut sub| {
+            assert_eq!(sub.len(), 3);
+            assert!(sub.lower(outside).is_none());
+            let i = sub.lower(inside).unwrap();
+            assert_eq!(sub[i], 3);
+            sub[i] = 30;
+            // a nested sub-scope
+            let r = sub.range();
+            sub.sub_scope(r, |subsub| subsub.lift_range(subsub.range()));
+            (sub.lift(i), sub.lift_range(sub.range()))
+        });
+        assert_eq!(lifted.0, inside);
+        assert_eq!(&v[lifted.1], &[2, 30, 4]);
+    });
+}
@@ -0,0 +1,183 @@
+//! A buffer of uninitialized memory, that tracks its initialized prefix.
+
+use std::mem::MaybeUninit;
+use std::ops;
+use std::ptr;
+use std::slice;
+
+use crate::index_error::{IndexingError, index_error};
+use crate::proof::Init;
+use crate::{Id, Index, Range};
+
+/// A branded buffer of possibly uninitialized elements.
+///
+/// The buffer is initialized from the front, one element at a time using
+/// `.write()`, and `.init_range()` returns the range that is known to be
+/// initialized. The buffer can be indexed like `&self[r]` where `r` is a
+/// `Range<'id, Init>`, without runtime bounds checking.
+///
+/// When the buffer is dropped, it drops the elements that are initialized.
+pub struct UninitBuffer<'id, 'a, T> {
+    #[allow(dead_code)]
+    id: Id<'id>,
+    buf: &'a mut [MaybeUninit<T>],
+    init: usize,
+}
+
+/// Create an indexing scope for a buffer of uninitialized elements.
+///
+/// The buffer starts out with no elements initialized.
+pub fn scope_uninit<'a, T, F, Out>(buf: &'a mut [MaybeUninit<T>], f: F) -> Out
+    where F: for<'id> FnOnce(UninitBuffer<'id, 'a, T>) -> Out,
+{
+    f(UninitBuffer { id: Id::default(), buf, init: 0 })
+}
+
+impl<'id, 'a, T> UninitBuffer<'id, 'a, T> {
+    /// Return the length of the buffer (including the uninitialized part).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Return `true` if the buffer has length zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the full range of the buffer.
+    #[inline]
+    pub fn range(&self) -> Range<'id> {
+        unsafe {
+            Range::from(0, self.len())
+        }
+    }
+
+    /// Vet the absolute `index`.
+    #[inline]
+    pub fn vet(&self, index: usize) -> Result<Index<'id>, IndexingError> {
+        self.range().contains(index).ok_or_else(index_error)
+    }
+
+    /// Return the range of the initialized elements, which is a prefix
+    /// of the buffer.
+    #[inline]
+    pub fn init_range(&self) -> Range<'id, Init> {
+        unsafe {
+            Range::from_any(0, self.init)
+        }
+    }
+
+    /// Return the range of the uninitialized elements, which starts at
+    /// the frontier of the initialized range.
+    #[inline]
+    pub fn uninit_range(&self) -> Range<'id> {
+        unsafe {
+            Range::from(self.init, self.len())
+        }
+    }
+
+    /// Initialize the element at `index` with `value`, which extends the
+    /// initialized range by one.
+    ///
+    /// `index` must be the first uninitialized index; otherwise an error is
+    /// returned, and `value` is dropped.
+    #[inline]
+    pub fn write(&mut self, index: Index<'id>, value: T) -> Result<Range<'id, Init>, IndexingError> {
+        if index.index != self.init {
+            return Err(index_error());
+        }
+        unsafe {
+            *self.buf.get_unchecked_mut(index.index) = MaybeUninit::new(value);
+        }
+        self.init += 1;
+        Ok(self.init_range())
+    }
+
+    /// Consume the buffer and return its initialized elements.
+    ///
+    /// The elements are no longer dropped by the buffer.
+    pub fn into_init(self) -> &'a mut [T] {
+        let init = self.init;
+        let ptr = self.buf.as_mut_ptr() as *mut T;
+        std::mem::forget(self);
+        unsafe {
+            slice::from_raw_parts_mut(ptr, init)
+        }
+    }
+}
+
+impl<'id, 'a, T> Drop for UninitBuffer<'id, 'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let r = self.init_range();
+            let init = &mut self[r] as *mut [T];
+            // the initialized range is reset first, in case dropping panics
+            self.init = 0;
+            ptr::drop_in_place(init);
+        }
+    }
+}
+
+/// `&self[r]` where `r` is a `Range<'id, Init>`.
+impl<'id, 'a, T> ops::Index<Range<'id, Init>> for UninitBuffer<'id, 'a, T> {
+    type Output = [T];
+    #[inline(always)]
+    fn index(&self, r: Range<'id, Init>) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(self.buf.as_ptr().add(r.start) as *const T, r.len())
+        }
+    }
+}
+
+/// `&mut self[r]` where `r` is a `Range<'id, Init>`.
+impl<'id, 'a, T> ops::IndexMut<Range<'id, Init>> for UninitBuffer<'id, 'a, T> {
+    #[inline(always)]
+    fn index_mut(&mut self, r: Range<'id, Init>) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(self.buf.as_mut_ptr().add(r.start) as *mut T, r.len())
+        }
+    }
+}
+
+#[test]
+fn test_uninit() {
+    use std::cell::Cell;
+
+    struct Counted<'a>(i32, &'a Cell<usize>);
+    impl<'a> Drop for Counted<'a> {
+        fn drop(&mut self) { self.1.set(self.1.get() + 1); }
+    }
+
+    let drops = Cell::new(0);
+    let mut buf: [MaybeUninit<Counted>; 4] = [(); 4].map(|_| MaybeUninit::uninit());
+    scope_uninit(&mut buf, |mut b| {
+        let mut r = b.init_range();
+        assert!(r.is_empty());
+        for i in b.range().split_at(3).0 {
+            r = b.write(i, Counted(i.integer() as i32, &drops)).unwrap();
+        }
+        assert!(b.write(b.vet(3).unwrap(), Counted(-1, &drops)).is_ok());
+        assert!(b.write(b.vet(0).unwrap(), Counted(-1, &drops)).is_err());
+        assert_eq!(drops.get(), 1);
+        assert_eq!(b[r].len(), 3);
+        assert_eq!(b[r][2].0, 2);
+        assert_eq!(b[b.init_range()].len(), 4);
+        assert!(b.uninit_range().is_empty());
+    });
+    assert_eq!(drops.get(), 5);
+
+    let out = scope_uninit(&mut buf[..3], |mut b| {
+        for i in b.range() {
+            b.write(i, Counted(1, &drops)).unwrap();
+        }
+        b.into_init()
+    });
+    assert_eq!(out.len(), 3);
+    assert_eq!(drops.get(), 5);
+    let sum: i32 = out.iter().map(|c| c.0).sum();
+    assert_eq!(sum, 3);
+    unsafe { ptr::drop_in_place(out) };
+    assert_eq!(drops.get(), 8);
+}
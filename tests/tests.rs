@@ -6,6 +6,7 @@ use indexing::scope;
 use indexing::algorithms::*;
 
 use std::cmp::Ordering;
+#[cfg(feature="experimental_pointer_ranges")]
 use std::fmt::Debug;
 
 
@@ -67,7 +68,7 @@ fn range_split_nonempty() {
             let r = v.vet_range(0..i).unwrap();
             if let Ok(r) = r.nonempty() {
                 let (a, b) = r.split_in_half();
-                assert!(b.len() > 0);
+                assert!(!b.is_empty());
                 assert_eq!(a.len() + b.len(), r.len());
                 assert!(b.first().integer() < r.len());
             } else {
@@ -86,6 +87,7 @@ fn is_sorted<T: Clone + Ord>(v: &[T]) -> bool {
     vec.sort();
     vec == v
 }
+#[cfg(feature="experimental_pointer_ranges")]
 fn sorted<T: Clone + Ord>(v: &[T]) -> Vec<T> {
     let mut v = v.to_vec();
     v.sort();
@@ -142,7 +144,7 @@ fn is_minheap<T: Ord>(v: &[T]) -> bool {
 #[test]
 fn qc_heapify() {
     fn prop(mut v: Vec<i32>) -> bool {
-        indexing::algorithms::heapify(&mut v);
+        indexing::algorithms::heapify(&mut v[..]);
         is_minheap(&v)
     }
     quickcheck::quickcheck(prop as fn(_) -> bool);
@@ -181,12 +183,12 @@ fn sorted_vec<T: Ord>(mut v: Vec<T>) -> Vec<T> { v.sort(); v }
 
 quickcheck! {
     fn test_lower_bound_1(data: Vec<u8>, find: u8) -> bool {
-        lower_bound(&data, &find) == lower_bound_raw_ptr(&data, &find)
+        lower_bound(&data[..], &find) == lower_bound_raw_ptr(&data, &find)
     }
 
     fn test_lower_bound_2(data: Vec<u8>, find: u8) -> bool {
         let data = sorted_vec(data);
-        lower_bound(&data, &find) ==
+        lower_bound(&data[..], &find) ==
             data.binary_search_by(|x|
                 if *x >= find {
                     Ordering::Greater
@@ -238,7 +240,9 @@ quickcheck! {
     }
 }
 
+#[cfg(feature="experimental_pointer_ranges")]
 type R = Result<usize, usize>;
+#[cfg(feature="experimental_pointer_ranges")]
 fn assert_binary_search_ok<T>(data: &[T], expected: R, result: R)
     where T: Debug + Ord
 {
@@ -248,3 +252,21 @@ fn assert_binary_search_ok<T>(data: &[T], expected: R, result: R)
         panic!("expected: {:?}, got: {:?}", expected, result);
     }
 }
+
+struct Stack<T> {
+    items: Vec<T>,
+}
+
+indexing::slice_backed!([T] Stack<T>, mut items: [T]);
+
+#[test]
+fn test_scope_slice_mut() {
+    let mut stack = Stack { items: vec![5, 4, 3, 2, 1] };
+    indexing::scope_slice_mut(&mut stack, |mut v| {
+        insertion_sort_ranges(&mut v[..], |a, b| a < b);
+        let r = v.range().nonempty().unwrap();
+        assert_eq!(v[r.first()], 1);
+        assert_eq!(v[r.last()], 5);
+    });
+    assert_eq!(stack.items, [1, 2, 3, 4, 5]);
+}
@@ -1,4 +1,4 @@
-#![cfg(feature="use_std")]
+#![cfg(feature="alloc")]
 
 extern crate indexing;
 
@@ -16,3 +16,98 @@ fn test_vec() {
     });
     assert_eq!(&v, &[0, 1, 1, 2]);
 }
+
+#[test]
+fn test_boxed_slice() {
+    let mut v = vec![3, 2, 1].into_boxed_slice();
+    scope(&mut v, |mut v| {
+        let r = v.range().nonempty().unwrap();
+        v.swap(r.first(), r.last());
+    });
+    assert_eq!(&*v, &[1, 2, 3]);
+}
+
+#[test]
+fn test_arc_slice() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let data: Arc<[i32]> = vec![1, 2, 3, 4].into();
+    let sum = scope(data, |v| {
+        let (a, b) = v.range().split_in_half();
+        let w = v.clone();
+        thread::scope(|s| {
+            let h = s.spawn(move || w[b].iter().sum::<i32>());
+            v[a].iter().sum::<i32>() + h.join().unwrap()
+        })
+    });
+    assert_eq!(sum, 10);
+}
+
+#[test]
+fn test_soa_vecs() {
+    use indexing::soa::scope_soa;
+
+    let columns = (vec![1, 2], vec!["a", "b"], vec![0.5, 1.5]);
+    let columns = scope_soa(columns, |mut t| {
+        let i = t.push((3, "c", 2.5));
+        for j in t.range() {
+            *t.get_mut(j).0 *= 10;
+        }
+        assert_eq!(t.get(i), (&30, &"c", &2.5));
+        t.into_inner()
+    }).unwrap();
+    assert_eq!(columns.0, [10, 20, 30]);
+    assert_eq!(columns.1, ["a", "b", "c"]);
+}
+
+#[test]
+fn test_shrink() {
+    let mut v = vec![0, 1, 2, 3, 4];
+    let n = scope(&mut v, |v| {
+        let v = v.only_index();
+        let i = v.vet(1).unwrap();
+        let j = v.vet(4).unwrap();
+        v.truncate(4, |v, remap| {
+            assert!(remap.index(j).is_none());
+            let i = remap.index(i).unwrap();
+            v.pop(|mut v, remap, last| {
+                assert_eq!(last, Some(3));
+                let i = remap.index(i).unwrap();
+                v[i] += 10;
+                let k = v.push(5);
+                assert_eq!(v[k], 5);
+                v.clear(|v, remap| {
+                    assert!(remap.index(k).is_none());
+                    v.len()
+                })
+            })
+        })
+    });
+    assert_eq!(n, 0);
+    assert!(v.is_empty());
+
+    // an owned vector
+    let popped = scope(vec![1, 2, 3], |v| {
+        v.only_index().pop(|v, _, last| (v.len(), last))
+    });
+    assert_eq!(popped, (2, Some(3)));
+}
+
+#[test]
+fn test_nested_vec() {
+    use indexing::segments::scope_nested;
+
+    let mut rows = vec![vec![1, 2], vec![], vec![3, 4, 5]];
+    scope_nested(&mut rows, |mut rows| {
+        let empty = rows.vet(1).unwrap();
+        rows.segment_mut(empty, |row| {
+            let mut row = row.only_index();
+            row.push(0);
+        });
+        let i = rows.vet(2).unwrap();
+        let last = rows.segment(i, |row| row[row.range().nonempty().unwrap().last()]);
+        assert_eq!(last, 5);
+    });
+    assert_eq!(rows, [vec![1, 2], vec![0], vec![3, 4, 5]]);
+}
@@ -0,0 +1,47 @@
+#![cfg(feature="alloc")]
+
+extern crate indexing;
+
+use std::collections::VecDeque;
+
+use indexing::scope;
+
+
+#[test]
+fn test_vec_deque() {
+    let mut v = VecDeque::new();
+    v.push_back(1);
+    v.push_back(2);
+    scope(&mut v, |v| {
+        let mut v = v.only_index();
+        let back = v.push(3);
+        let front = v.push_front(0);
+        assert_eq!(v[front], 0);
+        assert_eq!(v[back], 2);
+        v[back] = 4;
+    });
+    assert_eq!(v, [0, 1, 4, 3]);
+}
+
+#[test]
+fn test_vec_deque_range_slices() {
+    let mut v: VecDeque<_> = (3..6).collect();
+    for i in (0..3).rev() {
+        v.push_front(i);
+    }
+    scope(&mut v, |mut v| {
+        let r = v.range();
+        let (a, b) = v.range_slices(r);
+        assert_eq!(a.len() + b.len(), 6);
+        assert_eq!(a.iter().chain(b).cloned().collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5]);
+
+        let (front, back, _) = r.split_at(2);
+        let (a, b) = v.range_slices(front);
+        assert_eq!(a.iter().chain(b).cloned().collect::<Vec<_>>(), [0, 1]);
+        let (a, b) = v.range_slices_mut(back);
+        for elt in a.iter_mut().chain(b) {
+            *elt *= 10;
+        }
+    });
+    assert_eq!(v, [0, 1, 20, 30, 40, 50]);
+}